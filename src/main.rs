@@ -1,7 +1,5 @@
 use chrono::{DateTime, Utc};
-use crate::timeseries::Series;
-
-mod timeseries;
+use timeseries::timeseries::Series;
 
 fn main() {
 
@@ -1,218 +1,5121 @@
+use core::cmp::Ordering;
+use core::fmt::{self, Display, Write};
+use core::iter::Peekable;
+use core::ops::{Add, Div, Mul, RangeInclusive, Sub};
+use core::time::Duration;
 use heapless::Vec;
 
+#[cfg(feature = "approx")]
+pub use approx::ApproxEpsilon;
+pub use builder::SeriesBuilder;
+pub use counted::CountedSeries;
+pub use counting::CountingSeries;
+pub use debounced::DebouncedSeries;
+pub use encoding::{DecodeError, Delta, Encode, EncodeError};
+pub use ring::RingSeries;
+pub use streaming::StreamingCompressor;
+#[cfg(feature = "derive")]
+pub use timeseries_derive::Deviate;
+pub use view::SeriesView;
+
+#[cfg(feature = "approx")]
+mod approx;
+mod builder;
+mod counted;
+mod counting;
+mod debounced;
+mod encoding;
+mod integer;
+mod ring;
+mod streaming;
+mod view;
+
 #[derive(Debug, Eq, PartialEq)]
 pub struct Series<const N : usize, I, T> {
     pub max_deviation: T,
-    pub buckets: Vec<SerieEntry<I, T>, N>
+    pub buckets: Vec<SerieEntry<I, T>, N>,
+    config: Config<I, T>,
+    /// Set by [`Series::close`] to make the next reading open a new bucket.
+    closed: bool
 }
 
-impl <const N : usize, I : Ord, T : Deviate> Series<N, I, T> {
-    pub fn new(max_deviation: T) -> Series<N, I, T> {
-        Series { max_deviation, buckets: Vec::new() }
+/// Compression settings beyond `max_deviation`, chosen by the constructor or builder.
+#[derive(Debug, Eq, PartialEq)]
+struct Config<I, T> {
+    mode: Mode<T>,
+    evicting: bool,
+    max_span: Option<SpanLimit<I>>,
+    order: Option<Comparator<I>>,
+    strict: bool
+}
+
+impl <I, T> Default for Config<I, T> {
+    fn default() -> Self {
+        Config { mode: Mode::Absolute, evicting: false, max_span: None, order: None, strict: true }
     }
+}
 
-    pub fn append_monotonic(&mut self, at: I, value: T) -> bool {
-        if *&self.buckets.is_full() {
-            return false
-        } else {
-            return match self.buckets.pop() {
-                Some(v) => {
-                    let gt_start = &at > &v.range.start;
-                    let gt_end = &v.range.end.as_ref().map(|x| &at > x).unwrap_or(true);
-
-                    if gt_start && *gt_end {
-                        if v.value.deviate(&value, &self.max_deviation) {
-                            self.buckets.push(v);
-                            self.buckets.push(SerieEntry { range: Range::new(at), value });
-                        } else {
-                            let new_range = v.range.extend(at);
-                            self.buckets.push(SerieEntry { range: new_range, value: v.value });
-                        }
-                        true
-                    } else {
-                        self.buckets.push(v);
-                        false
-                    }
-                },
-                None => {
-                    self.buckets.push(SerieEntry { range: Range::new(at), value });
-                    true
-                }
-            }
-        }
+/// How an incoming value is compared against the current bucket's value.
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Mode<T> {
+    Absolute,
+    Asymmetric { down: T },
+    Hysteresis { close: T },
+    Norm(Norm<T>),
+    Relative(Relative<T>),
+    Sdt(Door<T>)
+}
+
+impl <T> Mode<T> {
+    fn norm(metric: Metric) -> Mode<T> where T: DeviateL1 + DeviateInf {
+        let deviates = match metric {
+            Metric::L1 => <T as DeviateL1>::deviate_l1,
+            Metric::Inf => <T as DeviateInf>::deviate_inf
+        };
+
+        Mode::Norm(Norm { metric, deviates })
     }
 
-    pub fn starts_at(&self) -> Option<&I> {
-        self.buckets.first().map(|x| &x.range.start)
+    fn relative() -> Mode<T> where T: DeviateRelative {
+        Mode::Relative(Relative { deviates: <T as DeviateRelative>::deviate_relative })
     }
 
-    pub fn ends_at(&self) -> Option<&I> {
-        let mut end: Option<&I> = None;
+    fn sdt() -> Mode<T> where T: SwingingDoor {
+        Mode::Sdt(Door { bounds: None, narrow: narrow_door::<T>, midpoint: <T as SwingingDoor>::midpoint })
+    }
+}
 
-        for b in &self.buckets {
-            end = Some(&b.range.start).max(end);
-            end = b.range.end.as_ref().max(end);
-        }
+/// The multi-channel comparison chosen by [`Series::new_metric`]. As with [`SpanLimit`] the
+/// comparison is a function pointer so that its bounds only apply to that constructor.
+struct Norm<T> {
+    metric: Metric,
+    deviates: fn(&T, &T, &T) -> bool
+}
 
-        return end
+impl <T> fmt::Debug for Norm<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Norm").field("metric", &self.metric).finish()
     }
+}
 
-    pub fn is_full(&self) -> bool {
-        return *&self.buckets.is_full()
+impl <T> PartialEq for Norm<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.metric == other.metric
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
-struct Range<I> {
-    start: I,
-    end: Option<I>
+impl <T> Eq for Norm<T> {}
+
+impl <T> Clone for Norm<T> {
+    fn clone(&self) -> Self {
+        Norm { metric: self.metric, deviates: self.deviates }
+    }
 }
 
-impl <I: Ord + Sized> Range<I> {
-    pub fn new(start: I) -> Range<I> {
-        Range { start, end: None }
+/// The comparison chosen by [`Series::new_relative`], stored as a function pointer like
+/// [`Norm`].
+struct Relative<T> {
+    deviates: fn(&T, &T, &T) -> bool
+}
+
+impl <T> fmt::Debug for Relative<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Relative").finish_non_exhaustive()
     }
+}
 
-    pub fn extend(self, value: I) -> Range<I> {
-        Range {
-            start: self.start,
-            end: Some(value)
-        }
+impl <T> PartialEq for Relative<T> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
     }
 }
 
-pub trait Deviate {
-    fn deviate(&self, other: &Self, max_deviation: &Self) -> bool;
+impl <T> Eq for Relative<T> {}
+
+impl <T> Clone for Relative<T> {
+    fn clone(&self) -> Self {
+        Relative { deviates: self.deviates }
+    }
 }
 
-impl Deviate for f32 {
-    fn deviate(&self, other: &Self, max_deviation: &Self) -> bool {
-        self - other > *max_deviation
+/// The swinging door of [`Series::new_sdt`]: the values within the threshold of every reading
+/// absorbed by the last bucket, as `(low, high)`, or `None` before the first reading.
+struct Door<T> {
+    bounds: Option<(T, T)>,
+    narrow: NarrowDoor<T>,
+    midpoint: fn(&(T, T)) -> T
+}
+
+impl <T : fmt::Debug> fmt::Debug for Door<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Door").field("bounds", &self.bounds).finish()
     }
 }
 
-impl Deviate for f64 {
-    fn deviate(&self, other: &Self, max_deviation: &Self) -> bool {
-        self - other > *max_deviation
+impl <T : PartialEq> PartialEq for Door<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bounds == other.bounds
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
-pub struct SerieEntry<I, T> {
-    range: Range<I>,
-    value: T
+impl <T : Eq> Eq for Door<T> {}
+
+impl <T : Clone> Clone for Door<T> {
+    fn clone(&self) -> Self {
+        Door { bounds: self.bounds.clone(), narrow: self.narrow, midpoint: self.midpoint }
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+type NarrowDoor<T> = fn(Option<&(T, T)>, &T, &T, &T) -> Option<(T, T)>;
 
-    #[test]
-    fn it_works() {
-        let mut timeseries: Series<10, u8, f32> = Series::new(0.3);
+/// Narrows the door of the bucket holding `bucket` by `value`. A door that no longer matches
+/// the bucket, because the bucket was changed by anything but appending, is opened afresh
+/// around the bucket's value.
+fn narrow_door<T: SwingingDoor>(door: Option<&(T, T)>, bucket: &T, value: &T, max_deviation: &T) -> Option<(T, T)> {
+    match door.filter(|door| T::midpoint(door) == *bucket) {
+        Some(door) => value.narrow(Some(door), max_deviation),
+        None => bucket.narrow(None, max_deviation).and_then(|door| value.narrow(Some(&door), max_deviation))
+    }
+}
 
-        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
-        assert!(timeseries.append_monotonic( 2_u8, 32.7f32));
-        assert!(timeseries.append_monotonic( 3_u8, 32.5f32));
-        assert!(timeseries.append_monotonic( 4_u8, 33.8f32));
-        assert!(timeseries.append_monotonic( 6_u8, 34.0f32));
-        assert!(timeseries.append_monotonic( 8_u8, 28.2f32));
-        assert!(timeseries.append_monotonic( 10_u8, 12.3f32));
+/// The longest span a bucket may cover. The check is stored as a function pointer so that
+/// timestamp arithmetic is only required of series constructed with a limit.
+struct SpanLimit<I> {
+    max_span: I,
+    exceeds: fn(&I, &I, &I) -> bool
+}
 
-        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+impl <I : fmt::Debug> fmt::Debug for SpanLimit<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpanLimit").field("max_span", &self.max_span).finish()
+    }
+}
 
-        expected.push(SerieEntry { range: Range { start: 1, end: Some(6) }, value: 32.6 });
-        expected.push(SerieEntry { range: Range { start: 8, end: None }, value: 28.2 });
-        expected.push(SerieEntry { range: Range { start: 10, end: None }, value: 12.3 });
+impl <I : PartialEq> PartialEq for SpanLimit<I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.max_span == other.max_span
+    }
+}
 
-        assert_eq!(timeseries.buckets, expected)
+impl <I : Eq> Eq for SpanLimit<I> {}
+
+/// A replacement for `I: Ord` in every ordering of timestamps, see [`Series::new_with_cmp`].
+struct Comparator<I> {
+    cmp: fn(&I, &I) -> Ordering
+}
+
+impl <I> fmt::Debug for Comparator<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Comparator").finish_non_exhaustive()
+    }
+}
+
+/// Function pointers cannot be compared meaningfully, so all comparators are considered equal.
+impl <I> PartialEq for Comparator<I> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
     }
+}
 
-    #[test]
-    fn should_not_append_when_full() {
-        let mut timeseries: Series<1, u8, f32> = Series::new(0.3);
+impl <I> Eq for Comparator<I> {}
 
-        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
-        assert!(!timeseries.append_monotonic( 2_u8, 32.7f32));
-        assert!(timeseries.is_full());
+impl <I> SpanLimit<I> {
+    fn new(max_span: I) -> SpanLimit<I> where I: Copy + Ord + Sub<Output = I> {
+        SpanLimit { max_span, exceeds: span_exceeds::<I> }
+    }
+}
 
-        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+fn span_exceeds<I: Copy + Ord + Sub<Output = I>>(start: &I, at: &I, max_span: &I) -> bool {
+    *at - *start > *max_span
+}
 
-        expected.push(SerieEntry { range: Range { start: 1, end: None }, value: 32.6 });
+impl <const N : usize, I : Ord, T : Deviate> Series<N, I, T> {
+    const NON_ZERO_CAPACITY: () = assert!(N > 0, "a Series needs a capacity of at least one bucket");
 
-        assert_eq!(timeseries.buckets, expected)
+    /// Creates a series that opens a new bucket when a reading deviates more than
+    /// `max_deviation` from the current bucket's value.
+    ///
+    /// A zero-capacity series could never hold a bucket, so `N == 0` is rejected at compile time:
+    ///
+    /// ```compile_fail
+    /// use timeseries::timeseries::Series;
+    ///
+    /// let _: Series<0, u8, f32> = Series::new(0.3);
+    /// ```
+    pub fn new(max_deviation: T) -> Series<N, I, T> {
+        Series::configured(max_deviation, Config::default())
     }
 
+    fn configured(max_deviation: T, config: Config<I, T>) -> Series<N, I, T> {
+        let () = Self::NON_ZERO_CAPACITY;
 
-    #[test]
-    fn should_return_false_when_append_is_not_monotonic_with_end() {
-        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+        Series { max_deviation, buckets: Vec::new(), config, closed: false }
+    }
 
-        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
-        assert!(timeseries.append_monotonic( 6_u8, 32.7f32));
-        assert!(!timeseries.append_monotonic( 1_u8, 32.5f32));
+    /// Wraps previously produced buckets in a series, for example after deserializing them.
+    /// The buckets are validated with [`Series::check_invariants`] and rejected when they are
+    /// out of order or contain a backwards range.
+    pub fn from_buckets(max_deviation: T, buckets: Vec<SerieEntry<I, T>, N>) -> Result<Series<N, I, T>, InvariantError> {
+        let series = Series { buckets, ..Series::new(max_deviation) };
 
-        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+        series.check_invariants().map(|()| series)
+    }
 
-        expected.push(SerieEntry { range: Range { start: 1, end: Some(6) }, value: 32.6 });
+    /// Compresses several timestamp-ordered sources into one series by repeatedly appending
+    /// the earliest pending reading across all sources. A reading that ties with or precedes the
+    /// last appended timestamp is dropped, and merging stops once the series is full.
+    pub fn from_merged<S: Iterator<Item = (I, T)>>(max_deviation: T, sources: &mut [Peekable<S>]) -> Series<N, I, T> where T: PartialEq {
+        let mut series = Series::new(max_deviation);
 
-        assert_eq!(timeseries.buckets, expected)
+        while !series.is_full() {
+            let earliest = sources.iter_mut().enumerate()
+                .filter_map(|(index, source)| source.peek().map(|(at, _)| (index, at)))
+                .fold(None, |earliest: Option<(usize, &I)>, (index, at)| match earliest {
+                    Some((_, min)) if min <= at => earliest,
+                    _ => Some((index, at))
+                })
+                .map(|(index, _)| index);
+
+            let Some((at, value)) = earliest.and_then(|index| sources[index].next()) else { break };
+
+            series.append_monotonic(at, value);
+        }
+
+        series
     }
 
-    #[test]
-    fn should_return_false_when_append_is_not_monotonic_without_end() {
-        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+    /// Compresses `points` into a new series, failing on the first point that cannot be
+    /// appended. Unlike [`Series::from_merged`] nothing is dropped silently, so malformed input
+    /// is reported with the index of the offending point.
+    pub fn try_from_slice(max_deviation: T, points: &[(I, T)]) -> Result<Series<N, I, T>, BuildError> where I: Clone, T: Clone + PartialEq {
+        if points.is_empty() {
+            return Err(BuildError::Empty)
+        }
 
-        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
-        assert!(timeseries.append_monotonic( 6_u8, 35.7f32));
-        assert!(!timeseries.append_monotonic( 1_u8, 32.5f32));
+        let mut series = Series::new(max_deviation);
 
-        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+        for (at_index, (at, value)) in points.iter().enumerate() {
+            if series.append_monotonic(at.clone(), value.clone()) {
+                continue
+            }
 
-        expected.push(SerieEntry { range: Range { start: 1, end: Some(6) }, value: 32.6 });
+            return match series.ends_at() {
+                Some(latest) if !series.may_follow(at, latest) => Err(BuildError::NonMonotonic { at_index }),
+                _ => Err(BuildError::Capacity { at_index })
+            }
+        }
 
-        assert_eq!(timeseries.buckets, expected)
+        Ok(series)
     }
 
-    #[test]
-    fn starts_at_some() {
-        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+    /// Fuses several series by compressing the endpoints of all their buckets in timestamp
+    /// order through [`Series::append_monotonic`], as [`Series::from_merged`] does. Fails with
+    /// the number of endpoints left over when the fused series runs out of capacity.
+    pub fn merge_all<const K : usize>(series: [Series<N, I, T>; K], max_deviation: T) -> Result<Series<N, I, T>, usize> where T: Clone + PartialEq {
+        let mut sources = series.map(|s| s.into_points().peekable());
+        let merged = Series::from_merged(max_deviation, &mut sources);
 
-        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
-        assert!(timeseries.append_monotonic( 2_u8, 32.7f32));
+        match sources.into_iter().map(Iterator::count).sum() {
+            0 => Ok(merged),
+            overflow => Err(overflow)
+        }
+    }
 
-        assert_eq!(timeseries.starts_at(), Some(&1_u8));
+    /// Creates a series with asymmetric thresholds: a reading opens a new bucket when it rises
+    /// more than `up` above, or drops more than `down` below, the current bucket's value.
+    pub fn new_asym(up: T, down: T) -> Series<N, I, T> {
+        Series::configured(up, Config { mode: Mode::Asymmetric { down }, ..Config::default() })
     }
 
-    #[test]
-    fn starts_at_none() {
-        let timeseries: Series<1, u8, f32> = Series::new(0.3f32);
-        assert_eq!(timeseries.starts_at(), None);
+    /// Creates a series that opens a new bucket when a reading moves more than `open_threshold`
+    /// away from the current bucket's value in either direction. While that new bucket holds
+    /// only its first reading, a reading that comes back within `close_threshold` of the
+    /// previous bucket's value drops it again and extends the previous bucket instead, so a
+    /// signal dithering around a level does not fragment into single-point buckets.
+    ///
+    /// Both thresholds are symmetric, like [`Series::new_asym`] with equal thresholds, whereas
+    /// [`Series::new`] on floats only splits on drops. A dithering signal that only rises above
+    /// its level therefore stays in one bucket under [`Series::new`] as well; hysteresis is the
+    /// symmetric mode that does not fragment it.
+    pub fn new_hysteresis(open_threshold: T, close_threshold: T) -> Series<N, I, T> {
+        Series::configured(open_threshold, Config { mode: Mode::Hysteresis { close: close_threshold }, ..Config::default() })
     }
 
-    #[test]
-    fn ends_at_some_range_end() {
-        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+    /// Creates a series for multi-channel values that combines the per-channel differences
+    /// with `metric`: [`Metric::Inf`] splits as soon as one channel moves too far, [`Metric::L1`]
+    /// also splits when several channels drift together.
+    pub fn new_metric(max_deviation: T, metric: Metric) -> Series<N, I, T> where T: DeviateL1 + DeviateInf {
+        Series::configured(max_deviation, Config { mode: Mode::norm(metric), ..Config::default() })
+    }
 
-        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
-        assert!(timeseries.append_monotonic( 2_u8, 32.7f32));
+    /// Creates a series that treats `max_ratio` as a fraction of the current bucket's value: a
+    /// reading opens a new bucket when it differs from that value by more than `max_ratio`
+    /// times its magnitude, in either direction.
+    pub fn new_relative(max_ratio: T) -> Series<N, I, T> where T: DeviateRelative {
+        Series::configured(max_ratio, Config { mode: Mode::relative(), ..Config::default() })
+    }
 
-        assert_eq!(timeseries.ends_at(), Some(&2_u8));
+    /// Creates a series using a swinging door for piecewise-constant buckets: a bucket stays
+    /// open while some value lies within `max_deviation` of every reading it absorbed, and
+    /// holds the middle of those values. Unlike [`Series::new`], which compares every reading
+    /// against the first one, the door narrows as readings arrive, so a slow drift is split
+    /// into buckets whose stored value is within `max_deviation` of all of their readings.
+    ///
+    /// A door left over from a last bucket that was changed by anything but appending, such as
+    /// [`Series::upsert`], is opened afresh around that bucket's value.
+    pub fn new_sdt(max_deviation: T) -> Series<N, I, T> where T: SwingingDoor {
+        Series::configured(max_deviation, Config { mode: Mode::sdt(), ..Config::default() })
     }
 
-    #[test]
-    fn ends_at_some_range_start() {
-        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+    /// Creates a series that orders timestamps with `cmp` instead of `I: Ord`, for example for
+    /// a wrapping tick counter. Appending, lookups by timestamp and [`Series::check_invariants`]
+    /// all use `cmp`, so a range may end at a timestamp that is smaller under `Ord`. Methods
+    /// that do arithmetic on timestamps, such as durations, gaps and resampling, still use the
+    /// operators of `I` and are only meaningful while the timestamps in the series do not wrap.
+    pub fn new_with_cmp(max_deviation: T, cmp: fn(&I, &I) -> Ordering) -> Series<N, I, T> {
+        Series::configured(max_deviation, Config { order: Some(Comparator { cmp }), ..Config::default() })
+    }
 
-        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
-        assert!(timeseries.append_monotonic( 2_u8, 35.7f32));
+    /// Creates a series whose buckets never span more than `max_span`: a reading that would
+    /// stretch the current bucket beyond it opens a new bucket even when it does not deviate.
+    pub fn new_with_max_span(max_deviation: T, max_span: I) -> Series<N, I, T> where I: Copy + Sub<Output = I> {
+        Series::configured(max_deviation, Config { max_span: Some(SpanLimit::new(max_span)), ..Config::default() })
+    }
 
-        assert_eq!(timeseries.ends_at(), Some(&2_u8));
+    /// Creates a series that, unlike every other constructor, also accepts a reading taken at
+    /// the same timestamp as the last one, for sources that can report twice within a tick.
+    /// Such a reading is merged when it does not deviate, which leaves the bucket unchanged.
+    /// When it does deviate it replaces the value of a single-point last bucket, or otherwise
+    /// opens a new bucket that starts where the last one ends. Earlier readings are still
+    /// rejected.
+    pub fn new_non_strict(max_deviation: T) -> Series<N, I, T> {
+        Series::configured(max_deviation, Config { strict: false, ..Config::default() })
     }
 
-    #[test]
-    fn ends_at_none() {
-        let timeseries: Series<1, u8, f32> = Series::new(0.3f32);
-        assert_eq!(timeseries.ends_at(), None);
+    /// How timestamps are ordered: with the comparator of [`Series::new_with_cmp`], or `Ord`.
+    fn order(&self) -> fn(&I, &I) -> Ordering {
+        self.config.order.as_ref().map(|order| order.cmp).unwrap_or(I::cmp)
+    }
+
+    /// Whether `at` comes strictly after `than`.
+    fn is_after(&self, at: &I, than: &I) -> bool {
+        (self.order())(at, than).is_gt()
+    }
+
+    fn deviates(&self, bucket: &T, value: &T) -> bool {
+        self.deviates_by(bucket, value, &self.max_deviation)
+    }
+
+    /// Like [`Series::deviates`], with `max_deviation` standing in for the stored threshold.
+    fn deviates_by(&self, bucket: &T, value: &T, max_deviation: &T) -> bool {
+        match &self.config.mode {
+            Mode::Absolute => bucket.deviate(value, max_deviation),
+            Mode::Asymmetric { down } => value.deviate_asym(bucket, max_deviation, down),
+            Mode::Hysteresis { .. } => value.deviate_asym(bucket, max_deviation, max_deviation),
+            Mode::Norm(norm) => (norm.deviates)(bucket, value, max_deviation),
+            Mode::Relative(relative) => (relative.deviates)(bucket, value, max_deviation),
+            Mode::Sdt(door) => (door.narrow)(door.bounds.as_ref(), bucket, value, max_deviation).is_none()
+        }
+    }
+
+    /// Whether `value` returns close enough to the previous bucket to undo the split that
+    /// opened the last bucket, see [`Series::new_hysteresis`].
+    fn rejoins_previous(&self, at: &I, value: &T) -> bool {
+        let Mode::Hysteresis { close } = &self.config.mode else { return false };
+
+        match self.buckets.as_slice() {
+            [.., previous, last] if last.range.end.is_none() => {
+                !value.deviate_asym(&previous.value, close, close) && !self.exceeds_span(&previous.range.start, at)
+            },
+            _ => false
+        }
+    }
+
+    fn exceeds_span(&self, start: &I, at: &I) -> bool {
+        self.config.max_span.as_ref().map(|limit| (limit.exceeds)(start, at, &limit.max_span)).unwrap_or(false)
+    }
+
+    /// Appends a reading taken at `at`. The reading extends the last bucket unless it deviates
+    /// from that bucket's value. A value equal to the bucket's value extends it without
+    /// consulting [`Deviate`] when [`Deviate::equal_may_deviate`] rules out a split, which
+    /// keeps flat signals cheap.
+    pub fn append_monotonic(&mut self, at: I, value: T) -> bool where T: PartialEq {
+        self.append_by(at, value, None)
+    }
+
+    /// Appends like [`Series::append_monotonic`], but compares this one reading against
+    /// `deviation` instead of `max_deviation`, for example to tolerate a known noisy burst.
+    /// The stored threshold is left unchanged.
+    pub fn append_with_deviation(&mut self, at: I, value: T, deviation: &T) -> bool where T: PartialEq {
+        self.append_by(at, value, Some(deviation))
+    }
+
+    /// Whether a reading at `at` may follow one at `latest`: strictly after it, or also at the
+    /// same time for a non-strict series.
+    fn may_follow(&self, at: &I, latest: &I) -> bool {
+        self.is_after(at, latest) || (!self.config.strict && !self.is_after(latest, at))
+    }
+
+    /// Whether a deviating reading at `at` replaces the value of the last bucket instead of
+    /// opening a new one, which a non-strict series does for a single-point bucket at `at`.
+    fn replaces_last(&self, at: &I) -> bool {
+        match self.buckets.last() {
+            Some(last) => last.range.end.is_none() && !self.is_after(at, &last.range.start),
+            None => false
+        }
+    }
+
+    /// Whether a reading would open a new bucket, or `None` when it would be rejected. This
+    /// does not account for a hysteresis series rejoining the previous bucket.
+    fn classify(&self, at: &I, value: &T, max_deviation: &T) -> Option<bool> where T: PartialEq {
+        if self.buckets.is_full() && !self.config.evicting {
+            return None
+        }
+
+        self.deviates_at(at, value, max_deviation)
+    }
+
+    /// Like [`Series::classify`], but regardless of capacity.
+    fn deviates_at(&self, at: &I, value: &T, max_deviation: &T) -> Option<bool> where T: PartialEq {
+        match self.buckets.last() {
+            Some(last) if !self.may_follow(at, last.range.latest()) => None,
+            Some(_) if self.closed => Some(true),
+            Some(last) if last.value == *value && !self.equal_may_deviate(max_deviation) => Some(self.exceeds_span(&last.range.start, at)),
+            Some(last) => Some(self.deviates_by(&last.value, value, max_deviation) || self.exceeds_span(&last.range.start, at)),
+            None => Some(true)
+        }
+    }
+
+    /// Whether a reading equal to the bucket's value may still deviate under the mode, in which
+    /// case the equal-value shortcut of [`Series::deviates_at`] must not be taken.
+    fn equal_may_deviate(&self, max_deviation: &T) -> bool {
+        match &self.config.mode {
+            Mode::Asymmetric { down } => T::equal_may_deviate(max_deviation) || T::equal_may_deviate(down),
+            _ => T::equal_may_deviate(max_deviation)
+        }
+    }
+
+    fn append_by(&mut self, at: I, value: T, max_deviation: Option<&T>) -> bool where T: PartialEq {
+        let Some(deviates) = self.classify(&at, &value, max_deviation.unwrap_or(&self.max_deviation)) else { return false };
+
+        self.commit(at, value, deviates, max_deviation)
+    }
+
+    /// Stores an accepted reading, given whether it deviates from the last bucket and the
+    /// threshold it was compared with, which defaults to `max_deviation`.
+    fn commit(&mut self, at: I, value: T, mut deviates: bool, max_deviation: Option<&T>) -> bool {
+        if deviates && self.rejoins_previous(&at, &value) {
+            let _ = self.buckets.pop();
+            deviates = false;
+        }
+
+        let replaces = deviates && self.replaces_last(&at);
+        let order = self.order();
+        let door = self.swing_door(&value, deviates, max_deviation.unwrap_or(&self.max_deviation));
+        let midpoint = match (&self.config.mode, &door) {
+            (Mode::Sdt(sdt), Some(bounds)) if !deviates => Some((sdt.midpoint)(bounds)),
+            _ => None
+        };
+
+        let stored = match self.buckets.last_mut() {
+            Some(last) if replaces => {
+                last.value = value;
+                true
+            },
+            Some(last) if !deviates => {
+                // Appending only accepts readings at or after the bucket's latest timestamp, so
+                // the only reading handed back is a repeated timestamp of a non-strict series at
+                // the start of a single-point bucket, which the bucket already covers.
+                if let Err(at) = last.range.try_extend_by(at, order) {
+                    debug_assert!(order(&at, &last.range.start).is_eq(), "appending would extend a bucket backwards");
+                }
+
+                if let Some(midpoint) = midpoint {
+                    last.value = midpoint;
+                }
+
+                true
+            },
+            _ => {
+                if self.buckets.is_full() && !self.buckets.is_empty() {
+                    self.buckets.remove(0);
+                }
+
+                self.buckets.push(SerieEntry { range: Range::new(at), value }).is_ok()
+            }
+        };
+
+        if let Mode::Sdt(sdt) = &mut self.config.mode {
+            sdt.bounds = door;
+        }
+
+        self.closed = false;
+
+        stored
+    }
+
+    /// The door of the last bucket once `value` is stored, for a series created with
+    /// [`Series::new_sdt`].
+    fn swing_door(&self, value: &T, deviates: bool, max_deviation: &T) -> Option<(T, T)> {
+        let Mode::Sdt(sdt) = &self.config.mode else { return None };
+
+        match self.buckets.last() {
+            Some(last) if !deviates => (sdt.narrow)(sdt.bounds.as_ref(), &last.value, value, max_deviation),
+            _ => (sdt.narrow)(None, value, value, max_deviation)
+        }
+    }
+
+    /// Appends like [`Series::append_monotonic`], but always makes room by evicting the oldest
+    /// bucket when a new bucket is needed and the series is full, and hands that bucket back,
+    /// for example to archive it. Returns `Ok(None)` when nothing was evicted and `Err(())` when
+    /// the reading is not later than the last one.
+    #[allow(clippy::result_unit_err)]
+    pub fn append_evicting_returning(&mut self, at: I, value: T) -> Result<Option<SerieEntry<I, T>>, ()> where T: PartialEq {
+        let deviates = self.deviates_at(&at, &value, &self.max_deviation).ok_or(())?;
+        let opens = deviates && !self.rejoins_previous(&at, &value) && !self.replaces_last(&at);
+        let evicted = if opens && self.buckets.is_full() { Some(self.buckets.remove(0)) } else { None };
+
+        self.commit(at, value, deviates, None);
+
+        Ok(evicted)
+    }
+
+    /// Reports what [`Series::append_monotonic`] would do with a reading, without appending it.
+    /// A reading that rejoins the previous bucket of a hysteresis series, or that replaces the
+    /// value of the last bucket of a non-strict series, counts as a merge.
+    pub fn would_merge(&self, at: &I, value: &T) -> MergeOutcome where T: PartialEq {
+        match self.classify(at, value, &self.max_deviation) {
+            None => MergeOutcome::Reject,
+            Some(true) if !self.rejoins_previous(at, value) && !self.replaces_last(at) => MergeOutcome::NewBucket,
+            Some(_) => MergeOutcome::Merge
+        }
+    }
+
+    /// Appends like [`Series::append_monotonic`] and returns the index of the bucket the reading
+    /// went into, or `None` when it was rejected. The reading always lands in the last bucket,
+    /// whether it extended that bucket or opened it.
+    pub fn append_indexed(&mut self, at: I, value: T) -> Option<usize> where T: PartialEq {
+        self.append_monotonic(at, value).then(|| self.buckets.len() - 1)
+    }
+
+    /// Appends like [`Series::append_monotonic`], but hands the reading to `on_full` when it
+    /// needs a new bucket and the series is full, for example to flush the buckets elsewhere.
+    /// Unlike [`Series::append_monotonic`], a reading that merges into the last bucket is still
+    /// accepted by a full series. Readings rejected for not being monotonic are dropped without
+    /// calling `on_full`.
+    pub fn append_or_else<F: FnMut(I, T)>(&mut self, at: I, value: T, mut on_full: F) -> bool where T: PartialEq {
+        let Some(deviates) = self.deviates_at(&at, &value, &self.max_deviation) else { return false };
+        let opens = deviates && !self.rejoins_previous(&at, &value) && !self.replaces_last(&at);
+
+        if opens && self.buckets.is_full() && !self.config.evicting {
+            on_full(at, value);
+            return false
+        }
+
+        self.commit(at, value, deviates, None)
+    }
+
+    pub fn starts_at(&self) -> Option<&I> {
+        self.view().starts_at()
+    }
+
+    pub fn ends_at(&self) -> Option<&I> {
+        self.view().ends_at()
+    }
+
+    pub fn value_at(&self, at: &I) -> Option<&T> {
+        self.view().value_at(at)
+    }
+
+    pub fn len(&self) -> usize {
+        self.view().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.view().is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.buckets.is_full()
+    }
+
+    pub fn last_bucket(&self) -> Option<&SerieEntry<I, T>> {
+        self.buckets.last()
+    }
+
+    /// Removes and returns the most recent bucket. This only ever removes a whole bucket: a
+    /// reading that extended a bucket's range cannot be undone on its own.
+    pub fn pop_last(&mut self) -> Option<SerieEntry<I, T>> {
+        self.buckets.pop()
+    }
+
+    /// The start and value of the earliest bucket.
+    pub fn first_point(&self) -> Option<(&I, &T)> {
+        self.buckets.first().map(|b| (&b.range.start, &b.value))
+    }
+
+    /// The latest timestamp and value of the most recent bucket.
+    pub fn last_point(&self) -> Option<(&I, &T)> {
+        self.buckets.last().map(|b| (b.range.latest(), &b.value))
+    }
+
+    /// Finalizes the last bucket if it is still open (`end == None`) by setting its `end` to
+    /// its `start`, producing a zero-width range. A bucket that already has an `end` is left
+    /// unchanged, as is an empty series.
+    ///
+    /// The next accepted reading always starts a new bucket, even when it stays within
+    /// `max_deviation` of the closed bucket, subject to the usual capacity and monotonicity
+    /// checks.
+    pub fn close(&mut self) where I: Clone {
+        if let Some(last) = self.buckets.last_mut() {
+            if last.range.end.is_none() {
+                last.range.end = Some(last.range.start.clone());
+            }
+
+            self.closed = true;
+        }
+    }
+
+    /// Samples the series as a step function on a grid from `starts_at` to `ends_at` in
+    /// increments of `step`. Each grid point yields the value of the bucket covering it; grid
+    /// points falling in a gap between buckets are handled according to `gaps`. A zero or
+    /// negative `step` yields nothing.
+    pub fn step_iter(&self, step: I, gaps: GapMode) -> impl Iterator<Item = (I, T)> + '_
+        where I: Copy + Default + Add<Output = I> + Sub<Output = I>, T: Clone {
+        let end = self.ends_at().copied();
+        let mut next = if step <= I::default() { None } else { self.starts_at().copied() };
+        let mut index = 0;
+        let order = self.order();
+
+        core::iter::from_fn(move || loop {
+            let at = next?;
+            next = end.filter(|end| *end - at >= step).map(|_| at + step);
+
+            while index + 1 < self.buckets.len() && order(&self.buckets[index + 1].range.start, &at).is_le() {
+                index += 1;
+            }
+
+            let bucket = &self.buckets[index];
+            let covered = order(&at, bucket.range.latest()).is_le();
+
+            if covered || gaps == GapMode::Hold {
+                return Some((at, bucket.value.clone()));
+            }
+        })
+    }
+
+    /// Adds `delta` to every bucket's `start` and `end` in place. Shifting all timestamps by
+    /// the same amount keeps the buckets ordered; use a negative `delta` on a signed index to
+    /// shift backwards.
+    pub fn shift_timestamps(&mut self, delta: I) where I: Add<Output = I> + Copy {
+        for b in &mut self.buckets {
+            b.range.start = b.range.start + delta;
+            b.range.end = b.range.end.map(|end| end + delta);
+        }
+    }
+
+    /// Coalesces consecutive buckets whose values are within `max_deviation` of each other,
+    /// scanning left to right and extending the earlier bucket to cover the later one. When
+    /// `max_gap` is given, buckets separated by more than `max_gap` are never merged.
+    pub fn merge_adjacent(&mut self, max_gap: Option<I>) where I: Copy + Sub<Output = I> {
+        let buckets = core::mem::take(&mut self.buckets);
+
+        for b in buckets {
+            let mergeable = self.buckets.last().map(|last| {
+                let last_end = *last.range.latest();
+                let within_gap = max_gap.map(|gap| b.range.start - last_end <= gap).unwrap_or(true);
+
+                within_gap && !self.deviates(&last.value, &b.value)
+            });
+
+            if let (Some(true), Some(last)) = (mergeable, self.buckets.last_mut()) {
+                last.range.end = Some(*b.range.latest());
+                continue;
+            }
+
+            let _ = self.buckets.push(b);
+        }
+    }
+
+    /// Yields `(prev_end, next_start)` for every pair of consecutive buckets separated by more
+    /// than one unit, i.e. the intervals for which no data was recorded.
+    pub fn gaps(&self) -> impl Iterator<Item = (I, I)> + '_ where I: Clone + Sub<Output = I> + From<u8> {
+        self.windows2().filter_map(|(prev, next)| {
+            let prev_end = prev.range.latest().clone();
+            let next_start = next.range.start.clone();
+
+            if next_start.clone() - prev_end.clone() > I::from(1) {
+                Some((prev_end, next_start))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Writes one `start,end,value` line per bucket, leaving the `end` field empty for
+    /// single-point buckets.
+    pub fn write_lines<W: Write>(&self, w: &mut W) -> fmt::Result where I: Display, T: Display {
+        for b in &self.buckets {
+            match &b.range.end {
+                Some(end) => writeln!(w, "{},{},{}", b.range.start, end, b.value)?,
+                None => writeln!(w, "{},,{}", b.range.start, b.value)?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the value of the bucket covering `at` or, when `at` falls in a gap or after the
+    /// last bucket, the value of the closest bucket before it. Only a timestamp preceding the
+    /// first bucket yields `None`.
+    pub fn nearest_value(&self, at: &I) -> Option<&T> {
+        let index = self.buckets.partition_point(|b| (self.order())(&b.range.start, at).is_le());
+        index.checked_sub(1).map(|i| &self.buckets[i].value)
+    }
+
+    /// Samples the step-function value at `start, start + step, ...` for up to `count` points
+    /// and appends each sample to a new series using `deviation` as its threshold, so samples
+    /// that do not deviate re-compress into shared entries. Grid points before the first bucket
+    /// are skipped and sampling stops at the first grid point past `ends_at`.
+    pub fn resample_uniform<const M: usize>(&self, start: I, step: I, count: usize, deviation: T) -> Series<M, I, T>
+        where I: Copy + Add<Output = I> + Sub<Output = I>, T: Clone + PartialEq {
+        let mut resampled = Series::new(deviation);
+        let Some(&end) = self.ends_at() else { return resampled };
+        let mut at = start;
+
+        for _ in 0..count {
+            if self.is_after(&at, &end) {
+                break;
+            }
+
+            if let Some(value) = self.nearest_value(&at) {
+                if !resampled.append_monotonic(at, value.clone()) {
+                    break;
+                }
+            }
+
+            // Stop before stepping past `end`, where `at + step` could overflow `I`.
+            if end - at < step {
+                break;
+            }
+
+            at = at + step;
+        }
+
+        resampled
+    }
+
+    /// Moves the buckets of `other` onto the end of this series. `other` must start strictly
+    /// after this series ends. When this series runs out of capacity, the number of buckets
+    /// moved so far is returned together with `other` holding the buckets that did not fit;
+    /// if `other` overlaps this series, nothing is moved and it is returned as is.
+    pub fn extend_from<const M: usize>(&mut self, mut other: Series<M, I, T>) -> Result<(), (usize, Series<M, I, T>)> {
+        if let (Some(end), Some(start)) = (self.ends_at(), other.starts_at()) {
+            if !self.is_after(start, end) {
+                return Err((0, other))
+            }
+        }
+
+        let mut buckets = core::mem::take(&mut other.buckets).into_iter();
+        let mut appended = 0;
+
+        while let Some(b) = buckets.next() {
+            if let Err(b) = self.buckets.push(b) {
+                let _ = other.buckets.push(b);
+                other.buckets.extend(buckets);
+                return Err((appended, other))
+            }
+
+            appended += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` when both series have exactly the same bucket ranges and every pair of
+    /// corresponding values lies within `tolerance` of each other in either direction. Range
+    /// boundaries are compared exactly; only values are compared approximately.
+    pub fn approx_eq(&self, other: &Self, tolerance: &T) -> bool {
+        self.buckets.len() == other.buckets.len()
+            && self.buckets.iter().zip(&other.buckets).all(|(a, b)| {
+                a.range == b.range && !a.value.deviate(&b.value, tolerance) && !b.value.deviate(&a.value, tolerance)
+            })
+    }
+
+    /// Merges runs of consecutive buckets whose values are exactly equal, extending the first
+    /// bucket of each run to cover the whole run. Unlike [`Series::merge_adjacent`] this
+    /// ignores `max_deviation`.
+    pub fn dedup_flat(&mut self) where T: PartialEq {
+        let buckets = core::mem::take(&mut self.buckets);
+
+        for b in buckets {
+            match self.buckets.last_mut() {
+                Some(last) if last.value == b.value => {
+                    last.range.end = Some(b.range.end.unwrap_or(b.range.start));
+                },
+                _ => {
+                    let _ = self.buckets.push(b);
+                }
+            }
+        }
+    }
+
+    /// Returns the smallest and largest bucket value in a single pass. Only each bucket's
+    /// representative value is considered, not the readings merged into it.
+    pub fn value_range(&self) -> Option<(T, T)> where T: PartialOrd + Clone {
+        let (min, max) = min_max(&self.buckets)?;
+        Some((min.clone(), max.clone()))
+    }
+
+    /// The buckets overlapping `[from, to]`, found by binary search.
+    fn overlapping(&self, from: &I, to: &I) -> &[SerieEntry<I, T>] {
+        let order = self.order();
+        let first = self.buckets.partition_point(|b| order(b.range.latest(), from).is_lt());
+        let last = self.buckets.partition_point(|b| order(&b.range.start, to).is_le()).max(first);
+
+        &self.buckets[first..last]
+    }
+
+    /// The difference between the largest and smallest bucket value among the buckets
+    /// overlapping `[from, to]`, or `None` when no bucket overlaps the window. Only each
+    /// bucket's representative value is considered, not interpolated extremes. For integer
+    /// values the subtraction may overflow, which panics in debug builds.
+    pub fn peak_to_peak(&self, from: &I, to: &I) -> Option<T> where T: PartialOrd + Clone + Sub<Output = T> {
+        let (min, max) = min_max(self.overlapping(from, to))?;
+        Some(max.clone() - min.clone())
+    }
+
+    /// Moves all buckets into a series with capacity `M`, keeping the compression settings.
+    /// When `M` cannot hold the current buckets the series is handed back unchanged in `Err`.
+    pub fn resize<const M: usize>(self) -> Result<Series<M, I, T>, Series<N, I, T>> {
+        if self.buckets.len() > M {
+            return Err(self)
+        }
+
+        let Series { max_deviation, buckets, config, closed } = self;
+        let mut resized = Series::configured(max_deviation, config);
+
+        resized.buckets.extend(buckets);
+        resized.closed = closed;
+
+        Ok(resized)
+    }
+
+    /// Splits the ranged bucket at `index` into `[start..at]` and `[at..end]`, both keeping
+    /// the original value. Returns `false` without changing anything when there is no such
+    /// ranged bucket, `at` is not strictly inside its range, or the series is full.
+    pub fn split_bucket(&mut self, index: usize, at: I) -> bool where I: Clone, T: Clone {
+        if self.buckets.is_full() {
+            return false
+        }
+
+        let order = self.order();
+        let Some(b) = self.buckets.get_mut(index) else {
+            return false
+        };
+
+        match &b.range.end {
+            Some(end) if order(&b.range.start, &at).is_lt() && order(&at, end).is_lt() => {
+                let tail = SerieEntry { range: Range { start: at.clone(), end: b.range.end.replace(at) }, value: b.value.clone() };
+                self.buckets.insert(index + 1, tail).is_ok()
+            },
+            _ => false
+        }
+    }
+
+    /// Snaps `value` to the nearest multiple of `quantum` before appending it, so readings that
+    /// only differ by noise collapse to the same value.
+    pub fn append_quantized(&mut self, at: I, value: T, quantum: &T) -> bool where T: Quantize + PartialEq {
+        self.append_monotonic(at, value.quantize(quantum))
+    }
+
+    /// Removes and returns the buckets that end strictly before `at`, oldest first. A bucket
+    /// straddling `at` stays in the series.
+    pub fn drain_before(&mut self, at: &I) -> Vec<SerieEntry<I, T>, N> {
+        let order = self.order();
+        let count = self.buckets.partition_point(|b| order(b.range.latest(), at).is_lt());
+        let mut buckets = core::mem::take(&mut self.buckets).into_iter();
+        let drained = buckets.by_ref().take(count).collect();
+
+        self.buckets.extend(buckets);
+
+        drained
+    }
+
+    /// Appends like [`Series::append_monotonic`], except that a reading at exactly the latest
+    /// timestamp corrects the reading stored there instead of being rejected. When the last
+    /// bucket is a single point its value is replaced, and it is then merged into the bucket
+    /// before it when the new value no longer deviates from that bucket's value and the merged
+    /// bucket stays within the span limit. When the last bucket is ranged only its last reading
+    /// is corrected: a deviating value is split off into a new single-point bucket touching it,
+    /// which fails when the series is full, and any other value already merges.
+    pub fn upsert(&mut self, at: I, value: T) -> bool where T: PartialEq {
+        let Some(last) = self.buckets.last() else { return self.append_monotonic(at, value) };
+
+        if !(self.order())(&at, last.range.latest()).is_eq() {
+            return self.append_monotonic(at, value)
+        }
+
+        if last.range.end.is_some() {
+            return !self.deviates(&last.value, &value) || self.buckets.push(SerieEntry { range: Range::new(at), value }).is_ok()
+        }
+
+        let merges = match self.buckets.len() {
+            len if len >= 2 => {
+                let prior = &self.buckets[len - 2];
+                !self.deviates(&prior.value, &value) && !self.exceeds_span(&prior.range.start, &at)
+            },
+            _ => false
+        };
+
+        if merges {
+            let _ = self.buckets.pop();
+
+            if let Some(prior) = self.buckets.last_mut() {
+                prior.range.end = Some(at);
+            }
+        } else if let Some(last) = self.buckets.last_mut() {
+            last.value = value;
+        }
+
+        true
+    }
+
+    /// Compresses `points` into a new series, stopping at the first point that cannot be
+    /// appended because the series is full or the point is not monotonic. Returns the series
+    /// and the index of that point, or `points.len()` when every point was appended.
+    pub fn compress_slice(max_deviation: T, points: &[(I, T)]) -> (Series<N, I, T>, usize) where I: Clone, T: Clone + PartialEq {
+        let mut series = Series::new(max_deviation);
+        let consumed = points.iter()
+            .take_while(|(at, value)| series.append_monotonic(at.clone(), value.clone()))
+            .count();
+
+        (series, consumed)
+    }
+
+    /// The buckets whose value is strictly above `threshold`.
+    pub fn buckets_above<'a>(&'a self, threshold: &'a T) -> impl Iterator<Item = &'a SerieEntry<I, T>> where T: PartialOrd {
+        self.buckets.iter().filter(move |b| b.value > *threshold)
+    }
+
+    /// The buckets whose value is strictly below `threshold`.
+    pub fn buckets_below<'a>(&'a self, threshold: &'a T) -> impl Iterator<Item = &'a SerieEntry<I, T>> where T: PartialOrd {
+        self.buckets.iter().filter(move |b| b.value < *threshold)
+    }
+
+    /// Verifies the ordering invariants the rest of the API relies on, reporting the first
+    /// violation found:
+    ///
+    /// * no range may end before it starts;
+    /// * each bucket must start after the latest timestamp of the bucket before it. The one
+    ///   exception is a bucket starting exactly where a ranged predecessor ends, which is what
+    ///   [`Series::split_bucket`] produces.
+    ///
+    /// Single-point buckets (`end == None`) are valid anywhere, since appending creates one for
+    /// every reading that deviates.
+    pub fn check_invariants(&self) -> Result<(), InvariantError> {
+        let order = self.order();
+
+        for (index, b) in self.buckets.iter().enumerate() {
+            if b.range.end.as_ref().map(|end| order(end, &b.range.start).is_lt()).unwrap_or(false) {
+                return Err(InvariantError::BackwardsRange { index })
+            }
+
+            if let Some(prev) = index.checked_sub(1).map(|i| &self.buckets[i]) {
+                let touches = prev.range.end.as_ref() == Some(&b.range.start);
+
+                if order(&b.range.start, prev.range.latest()).is_le() && !touches {
+                    return Err(InvariantError::NotMonotonic { index })
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The time range of each bucket, in order, without the values.
+    pub fn iter_ranges(&self) -> impl Iterator<Item = &Range<I>> {
+        self.buckets.iter().map(|b| &b.range)
+    }
+
+    /// Sums how long the signal spent in each value band. `edges` must be sorted ascending;
+    /// band `i` covers values from `edges[i]` up to, but excluding, `edges[i + 1]`, and the last
+    /// band is open-ended. Each band is reported as its lower edge with the accumulated
+    /// duration. Values below the first edge and single-point buckets contribute nothing, and
+    /// only the first `B` edges are used.
+    pub fn time_in_bands<const B: usize>(&self, edges: &[T]) -> Vec<(T, I), B>
+        where I: Copy + Default + Add<Output = I> + Sub<Output = I>, T: PartialOrd + Clone {
+        let mut bands: Vec<(T, I), B> = edges.iter().take(B).map(|edge| (edge.clone(), I::default())).collect();
+
+        for b in &self.buckets {
+            let Some(end) = b.range.end else { continue };
+            let band = bands.iter().rposition(|(edge, _)| b.value >= *edge);
+
+            if let Some(band) = band {
+                bands[band].1 = bands[band].1 + (end - b.range.start);
+            }
+        }
+
+        bands
+    }
+
+    pub fn as_slice(&self) -> &[SerieEntry<I, T>] {
+        &self.buckets
+    }
+
+    /// Consumes the series and returns its buckets. The `max_deviation` and any other
+    /// configuration are discarded.
+    pub fn into_buckets(self) -> Vec<SerieEntry<I, T>, N> {
+        self.buckets
+    }
+
+    /// Splits the covered span into consecutive tiles of `window` starting at `starts_at` and
+    /// reports `(tile_start, min, max)` of the bucket values overlapping each tile. Tiles that
+    /// no bucket overlaps are skipped, and at most `W` tiles are reported.
+    pub fn envelope<const W: usize>(&self, window: I) -> Vec<(I, T, T), W>
+        where I: Copy + Add<Output = I> + Sub<Output = I> + From<u8>, T: PartialOrd + Clone {
+        let mut envelope = Vec::new();
+        let (Some(&start), Some(&end)) = (self.starts_at(), self.ends_at()) else { return envelope };
+
+        if window == I::from(0) {
+            return envelope
+        }
+
+        let mut tile = start;
+
+        loop {
+            // The last tile is clipped to `end`, so its end cannot overflow `I`.
+            let tile_end = if end - tile < window { end } else { tile + (window - I::from(1)) };
+
+            if let Some((min, max)) = min_max(self.overlapping(&tile, &tile_end)) {
+                if envelope.push((tile, min.clone(), max.clone())).is_err() {
+                    break;
+                }
+            }
+
+            if end - tile < window {
+                break;
+            }
+
+            tile = tile + window;
+        }
+
+        envelope
+    }
+
+    /// Runs [`Series::merge_adjacent`] without a gap limit followed by [`Series::dedup_flat`]
+    /// and returns how many buckets were reclaimed.
+    pub fn compact(&mut self) -> usize where I: Copy + Sub<Output = I>, T: PartialEq {
+        let before = self.buckets.len();
+
+        self.merge_adjacent(None);
+        self.dedup_flat();
+
+        before - self.buckets.len()
+    }
+
+    /// Recomputes how many of `original_points` fall within each bucket's range, in bucket
+    /// order. Points outside every bucket are not counted.
+    pub fn run_lengths(&self, original_points: &[(I, T)]) -> Vec<usize, N> {
+        let mut counts: Vec<usize, N> = self.buckets.iter().map(|_| 0).collect();
+        let order = self.order();
+
+        for (at, _) in original_points {
+            let index = match self.buckets.partition_point(|b| order(&b.range.start, at).is_le()) {
+                0 => continue,
+                after => after - 1
+            };
+
+            if order(at, self.buckets[index].range.latest()).is_le() {
+                counts[index] += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// Compares only the buckets of both series, ignoring `max_deviation` and the rest of the
+    /// configuration.
+    pub fn buckets_eq(&self, other: &Self) -> bool where T: PartialEq {
+        self.buckets == other.buckets
+    }
+
+    /// Fills `out` with the step-function value at `start, start + step, ...`, holding the last
+    /// known value across gaps. Returns the number of samples written, which is less than
+    /// `out.len()` when the grid runs past `ends_at` or `start` precedes the first bucket. Like
+    /// [`Series::step_iter`], a zero or negative `step` writes nothing and returns 0.
+    pub fn reconstruct_into(&self, start: I, step: I, out: &mut [T]) -> usize
+        where I: Copy + Default + Add<Output = I> + Sub<Output = I>, T: Clone {
+        let Some(&end) = self.ends_at() else { return 0 };
+
+        if step <= I::default() {
+            return 0
+        }
+        let mut at = start;
+        let mut written = 0;
+
+        for sample in out.iter_mut() {
+            match self.nearest_value(&at) {
+                Some(value) if !self.is_after(&at, &end) => *sample = value.clone(),
+                _ => break
+            }
+
+            written += 1;
+
+            if end - at < step {
+                break;
+            }
+
+            at = at + step;
+        }
+
+        written
+    }
+
+    /// Makes room in a full series by multiplying `max_deviation` by `factor` and running
+    /// [`Series::merge_adjacent`] with the coarser threshold. Does nothing when the series is
+    /// not full. Every call permanently loosens the threshold, so fidelity degrades over time.
+    /// For integer values the multiplication may overflow, which panics in debug builds.
+    pub fn auto_coarsen(&mut self, factor: T) where I: Copy + Sub<Output = I>, T: Clone + Mul<Output = T> {
+        if !self.is_full() {
+            return
+        }
+
+        self.max_deviation = self.max_deviation.clone() * factor;
+        self.merge_adjacent(None);
+    }
+
+    /// Estimates how many raw readings were compressed, assuming they arrived every `period`:
+    /// `(end - start) / period + 1` for ranged buckets and one for single-point buckets.
+    /// Returns `0` when `period` is zero.
+    pub fn estimated_samples(&self, period: I) -> usize
+        where I: Copy + Sub<Output = I> + Div<Output = I> + From<u8> + TryInto<usize> {
+        if period == I::from(0) {
+            return 0
+        }
+
+        self.buckets.iter().map(|b| match b.range.end {
+            Some(end) => ((end - b.range.start) / period).try_into().unwrap_or(usize::MAX).saturating_add(1),
+            None => 1
+        }).fold(0, usize::saturating_add)
+    }
+
+    /// The buckets overlapping `range`, e.g. `series.buckets_in(10..=20)`.
+    pub fn buckets_in(&self, range: RangeInclusive<I>) -> impl Iterator<Item = &SerieEntry<I, T>> {
+        self.overlapping(range.start(), range.end()).iter()
+    }
+
+    /// The number of buckets overlapping `[from, to]`, found by binary search without visiting
+    /// the buckets in between.
+    pub fn count_in(&self, from: &I, to: &I) -> usize {
+        self.overlapping(from, to).len()
+    }
+
+    /// The fraction of `[from, to]` covered by buckets, from `0.0` to `1.0`. Buckets reaching
+    /// outside the window are clipped to it and single-point buckets cover nothing. An empty or
+    /// backwards window has no coverage.
+    pub fn coverage(&self, from: &I, to: &I) -> f32 where I: Copy + Sub<Output = I> + Into<u64> {
+        let order = self.order();
+
+        if order(to, from).is_le() {
+            return 0.0
+        }
+
+        let covered: u64 = self.overlapping(from, to).iter()
+            .filter_map(|b| b.range.end.map(|end| (core::cmp::max_by(b.range.start, *from, order), core::cmp::min_by(end, *to, order))))
+            .map(|(start, end)| (end - start).into())
+            .sum();
+
+        covered as f32 / (*to - *from).into() as f32
+    }
+
+    /// Yields every pair of consecutive buckets, in order, for metrics between buckets such as
+    /// gaps, slopes and transitions.
+    pub fn windows2(&self) -> impl Iterator<Item = (&SerieEntry<I, T>, &SerieEntry<I, T>)> {
+        self.buckets.windows(2).map(|pair| (&pair[0], &pair[1]))
+    }
+
+    /// Whether every bucket value lies within `max_deviation` of the first, in either direction.
+    /// An empty series is flat.
+    pub fn is_flat(&self) -> bool {
+        match self.buckets.first() {
+            Some(first) => self.buckets.iter().all(|b| !b.value.deviate_asym(&first.value, &self.max_deviation, &self.max_deviation)),
+            None => true
+        }
+    }
+
+    /// Classifies the sequence of bucket values. A series that is not [flat](Series::is_flat)
+    /// is increasing or decreasing when no consecutive pair of buckets moves the other way.
+    pub fn trend(&self) -> Trend where T: PartialOrd {
+        if self.is_flat() {
+            return Trend::Flat
+        }
+
+        let rising = self.windows2().all(|(prev, next)| next.value >= prev.value);
+        let falling = self.windows2().all(|(prev, next)| next.value <= prev.value);
+
+        match (rising, falling) {
+            (true, _) => Trend::Increasing,
+            (_, true) => Trend::Decreasing,
+            _ => Trend::Mixed
+        }
+    }
+
+    /// The number of significant value changes: every bucket after the first was opened by a
+    /// reading that deviated from the bucket before it (or by a span limit).
+    pub fn transitions(&self) -> usize {
+        self.buckets.len().saturating_sub(1)
+    }
+
+    /// The number of boundaries between consecutive buckets whose values differ by more than
+    /// `threshold` in either direction, typically a threshold larger than `max_deviation`.
+    pub fn transitions_exceeding(&self, threshold: &T) -> usize {
+        self.windows2().filter(|(prev, next)| next.value.deviate_asym(&prev.value, threshold, threshold)).count()
+    }
+
+    /// Yields each bucket's start and, for ranged buckets, its end, paired with the bucket's
+    /// value, in ascending time order. Everything is borrowed, so neither `I` nor `T` needs to
+    /// be `Clone`.
+    pub fn point_refs(&self) -> impl Iterator<Item = (&I, &T)> {
+        self.buckets.iter().flat_map(|b| {
+            core::iter::once((&b.range.start, &b.value)).chain(b.range.end.as_ref().map(|end| (end, &b.value)))
+        })
+    }
+
+    /// The start of the bucket holding the largest value, the earliest one on ties.
+    pub fn time_of_max(&self) -> Option<&I> where T: PartialOrd {
+        self.buckets.iter()
+            .reduce(|max, b| if b.value > max.value { b } else { max })
+            .map(|b| &b.range.start)
+    }
+
+    /// The start of the bucket holding the smallest value, the earliest one on ties.
+    pub fn time_of_min(&self) -> Option<&I> where T: PartialOrd {
+        self.buckets.iter()
+            .reduce(|min, b| if b.value < min.value { b } else { min })
+            .map(|b| &b.range.start)
+    }
+
+    /// Appends the endpoints of every bucket of `other` through [`Series::append_monotonic`],
+    /// so the seam between both series is compressed with this series' settings instead of
+    /// keeping `other`'s bucket boundaries. On failure the index of the first bucket of `other`
+    /// that could not be fully appended is returned; endpoints appended before it are kept.
+    pub fn merge_recompress(&mut self, other: &Series<N, I, T>) -> Result<(), usize> where I: Clone, T: Clone + PartialEq {
+        for (index, b) in other.buckets.iter().enumerate() {
+            let ends = core::iter::once(&b.range.start).chain(b.range.end.as_ref());
+
+            for at in ends {
+                if !self.append_monotonic(at.clone(), b.value.clone()) {
+                    return Err(index)
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The area under the step function: each ranged bucket contributes its value times its
+    /// duration, single-point buckets contribute nothing. Returns `None` instead of panicking
+    /// when a duration does not fit in `T` or the arithmetic overflows. There is no unchecked
+    /// integral; the methods that use the operators of `T` instead, such as
+    /// [`Series::peak_to_peak`] and [`Series::auto_coarsen`], may panic on integer overflow in
+    /// debug builds.
+    pub fn checked_integral(&self) -> Option<T> where I: Copy + Sub<Output = I>, T: CheckedArith + TryFrom<I> {
+        self.buckets.iter().filter_map(|b| b.range.end.map(|end| (end - b.range.start, &b.value)))
+            .try_fold(T::ZERO, |sum, (duration, value)| sum.checked_add(&value.checked_mul(&T::try_from(duration).ok()?)?))
+    }
+
+    /// The time-weighted mean, [`Series::checked_integral`] divided by the total duration of the
+    /// ranged buckets. Returns `None` on overflow and when no bucket has a duration.
+    pub fn checked_mean(&self) -> Option<T> where I: Copy + Sub<Output = I>, T: CheckedArith + TryFrom<I> {
+        let duration = self.buckets.iter().filter_map(|b| b.range.end.map(|end| end - b.range.start))
+            .try_fold(T::ZERO, |sum, duration| sum.checked_add(&T::try_from(duration).ok()?))?;
+
+        self.checked_integral()?.checked_div(&duration)
+    }
+
+    /// Keeps only the buckets overlapping the last `span` before `ends_at`: every bucket ending
+    /// strictly before `ends_at - span` is removed, a bucket straddling that cutoff is kept.
+    pub fn retain_recent(&mut self, span: I) where I: Copy + Sub<Output = I> {
+        let (Some(&start), Some(&end)) = (self.starts_at(), self.ends_at()) else { return };
+
+        if end - start > span {
+            self.drain_before(&(end - span));
+        }
+    }
+
+    /// The size in bytes of a series with this capacity, for sizing static allocations.
+    pub const fn footprint() -> usize {
+        core::mem::size_of::<Series<N, I, T>>()
+    }
+
+    /// The size in bytes of a single bucket; each unit of capacity costs about this much.
+    pub const fn bucket_size() -> usize {
+        core::mem::size_of::<SerieEntry<I, T>>()
+    }
+
+    /// Extends the last bucket to end at `at` without appending a reading, for a value known to
+    /// have held until then. Returns `false` when the series is empty or `at` is not strictly
+    /// later than the last bucket's latest timestamp.
+    pub fn extend_last_to(&mut self, at: I) -> bool {
+        let later = self.buckets.last().map(|last| self.is_after(&at, last.range.latest())).unwrap_or(false);
+        let order = self.order();
+
+        match self.buckets.last_mut() {
+            Some(last) if later => last.range.try_extend_by(at, order).is_ok(),
+            _ => false
+        }
+    }
+
+    /// Best-effort recovery of a corrupted series: sorts the buckets by start, then drops every
+    /// bucket with a backwards range or starting at or before the latest timestamp of the
+    /// bucket kept before it. Returns the number of dropped buckets. This is lossy, and when
+    /// several buckets share a start it is unspecified which of them survives.
+    pub fn repair(&mut self) -> usize {
+        let before = self.buckets.len();
+
+        let order = self.order();
+
+        self.buckets.sort_unstable_by(|a, b| order(&a.range.start, &b.range.start));
+
+        for b in core::mem::take(&mut self.buckets) {
+            let backwards = b.range.end.as_ref().map(|end| order(end, &b.range.start).is_lt()).unwrap_or(false);
+            let overlaps = self.buckets.last().map(|last| order(&b.range.start, last.range.latest()).is_le()).unwrap_or(false);
+
+            if !backwards && !overlaps {
+                let _ = self.buckets.push(b);
+            }
+        }
+
+        before - self.buckets.len()
+    }
+
+    /// The value at the halfway mark when the bucket values are sorted and each is weighted by
+    /// its bucket's duration. Single-point buckets weigh `point_weight`. Returns `None` for an
+    /// empty series, or when the total weight overflows `I`.
+    pub fn weighted_median(&self, point_weight: I) -> Option<T>
+        where I: Copy + Add<Output = I> + Sub<Output = I> + CheckedArith, T: Ord + Clone {
+        let weight = |b: &SerieEntry<I, T>| b.range.end.map(|end| end - b.range.start).unwrap_or(point_weight);
+        let mut sorted: Vec<&SerieEntry<I, T>, N> = self.buckets.iter().collect();
+
+        sorted.sort_unstable_by(|a, b| a.value.cmp(&b.value));
+
+        let total = sorted.iter().try_fold(I::ZERO, |total, b| total.checked_add(&weight(b)))?;
+        let mut accumulated = I::ZERO;
+
+        // Partial sums never exceed `total`, so they cannot overflow once `total` did not.
+        sorted.iter().find(|b| {
+            accumulated = accumulated + weight(b);
+            accumulated >= total - accumulated
+        }).map(|b| b.value.clone())
+    }
+
+    /// Converts every bucket boundary with `f`, for example to change the timestamp unit. `f`
+    /// must be strictly monotonic for the result to keep the bucket ordering. The comparison
+    /// mode, eviction and strictness carry over. A span limit and a comparator do not, since
+    /// both are expressed in `I` and cannot be converted by `f`; the result is ordered by
+    /// `J: Ord`, so `f` should also unwrap timestamps ordered by a comparator.
+    pub fn map_timestamps<J : Ord, F : Fn(&I) -> J>(&self, f: F) -> Series<N, J, T> where T: Clone {
+        let buckets = self.buckets.iter().map(|b| SerieEntry {
+            range: Range { start: f(&b.range.start), end: b.range.end.as_ref().map(&f) },
+            value: b.value.clone()
+        }).collect();
+        let config = Config { mode: self.config.mode.clone(), evicting: self.config.evicting, strict: self.config.strict, ..Config::default() };
+
+        Series { max_deviation: self.max_deviation.clone(), buckets, config, closed: self.closed }
+    }
+
+    /// Every distinct bucket boundary in ascending order: each start and, for ranged buckets,
+    /// each end. A boundary shared by two touching buckets is yielded once.
+    pub fn boundaries(&self) -> impl Iterator<Item = I> + '_ where I: Clone {
+        let mut previous: Option<&I> = None;
+
+        self.buckets.iter()
+            .flat_map(|b| core::iter::once(&b.range.start).chain(b.range.end.as_ref()))
+            .filter(move |at| {
+                let distinct = previous != Some(*at);
+                previous = Some(*at);
+                distinct
+            })
+            .cloned()
+    }
+
+    /// Groups runs of consecutive buckets with equal values, yielding for each run the range
+    /// from the first bucket's start to the last bucket's latest timestamp. This is the
+    /// read-only counterpart of [`Series::dedup_flat`].
+    pub fn merged_runs(&self) -> impl Iterator<Item = (Range<I>, &T)> where I: Clone, T: PartialEq {
+        let mut buckets = self.buckets.iter().peekable();
+
+        core::iter::from_fn(move || {
+            let first = buckets.next()?;
+            let mut end = first.range.end.clone();
+
+            while let Some(b) = buckets.next_if(|b| b.value == first.value) {
+                end = Some(b.range.latest().clone());
+            }
+
+            Some((Range { start: first.range.start.clone(), end }, &first.value))
+        })
+    }
+
+    /// Consumes the series and yields the start of every bucket and, for ranged buckets, the
+    /// end, each paired with the bucket's value, in ascending time order. Timestamps are moved
+    /// out; only the value of a ranged bucket is cloned to pair it with both endpoints.
+    pub fn into_points(self) -> impl Iterator<Item = (I, T)> where T: Clone {
+        self.buckets.into_iter().flat_map(|SerieEntry { range: Range { start, end }, value }| {
+            let points = match end {
+                Some(end) => [Some((start, value.clone())), Some((end, value))],
+                None => [Some((start, value)), None]
+            };
+
+            points.into_iter().flatten()
+        })
+    }
+
+    /// The local rate of change at `at`: the change in value from the bucket covering `at` to
+    /// the next bucket, per unit of time between the end of the one and the start of the other.
+    /// `None` when `at` falls in a gap or in the last bucket, or when the next bucket starts
+    /// where the covering one ends.
+    pub fn slope_at(&self, at: &I) -> Option<T>
+        where I: Copy + Sub<Output = I>, T: Clone + Sub<Output = T> + Scale<I> {
+        let order = self.order();
+        let index = self.buckets.partition_point(|b| order(&b.range.start, at).is_le()).checked_sub(1)?;
+        let (this, next) = (&self.buckets[index], self.buckets.get(index + 1)?);
+        let from = *this.range.latest();
+
+        if order(at, &from).is_gt() || order(&next.range.start, &from).is_le() {
+            return None
+        }
+
+        Some((next.value.clone() - this.value.clone()).per(&(next.range.start - from)))
+    }
+
+    /// The value at `at`: the covering bucket's value, or otherwise whatever `fill` derives
+    /// from the neighbouring buckets.
+    pub fn value_at_with(&self, at: &I, fill: GapFill) -> Option<T> where T: Clone + Interpolate<I> {
+        if let Some(value) = self.value_at(at) {
+            return Some(value.clone())
+        }
+
+        let index = self.following(at);
+        let previous = index.checked_sub(1).map(|i| &self.buckets[i]);
+
+        match (fill, previous, self.buckets.get(index)) {
+            (GapFill::HoldLast, Some(previous), _) => Some(previous.value.clone()),
+            (GapFill::HoldNext, _, Some(next)) => Some(next.value.clone()),
+            (GapFill::Linear, Some(previous), Some(next)) => {
+                T::interpolate((previous.range.latest(), &previous.value), (&next.range.start, &next.value), at)
+            },
+            _ => None
+        }
+    }
+
+    /// The index of the first bucket starting after `at`.
+    fn following(&self, at: &I) -> usize {
+        self.buckets.partition_point(|b| (self.order())(&b.range.start, at).is_le())
+    }
+
+    /// Replaces `max_deviation` for future appends and returns the previous threshold.
+    /// Existing buckets are left as they were compressed.
+    pub fn reset_deviation(&mut self, max_deviation: T) -> T {
+        core::mem::replace(&mut self.max_deviation, max_deviation)
+    }
+
+    /// Whether any two consecutive bucket values deviate from each other under `deviation`,
+    /// compared the same way appending compares readings. Only bucket values are inspected,
+    /// since the readings merged into a bucket are not kept.
+    pub fn violates_deviation(&self, deviation: &T) -> bool {
+        self.windows2().any(|(prev, next)| self.deviates_by(&prev.value, &next.value, deviation))
+    }
+
+    /// Moves all buckets of `other` onto the end of this series, unchanged. Nothing is moved
+    /// when `other` does not start after this series ends or when the buckets would not fit;
+    /// `other` is then handed back along with the reason.
+    pub fn merge(&mut self, other: Series<N, I, T>) -> Result<(), (MergeError, Series<N, I, T>)> {
+        if let (Some(end), Some(start)) = (self.ends_at(), other.starts_at()) {
+            if !self.is_after(start, end) {
+                return Err((MergeError::Overlap, other))
+            }
+        }
+
+        if self.buckets.len() + other.buckets.len() > N {
+            return Err((MergeError::Capacity, other))
+        }
+
+        self.buckets.extend(other.buckets);
+
+        Ok(())
+    }
+}
+
+/// Why [`Series::try_from_slice`] could not build a series.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BuildError {
+    /// The point at `at_index` needed a bucket after the series was full.
+    Capacity { at_index: usize },
+    /// The point at `at_index` is not later than the point before it.
+    NonMonotonic { at_index: usize },
+    /// There were no points.
+    Empty
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::Capacity { at_index } => write!(f, "point {} does not fit in the series", at_index),
+            BuildError::NonMonotonic { at_index } => write!(f, "point {} is not later than the previous point", at_index),
+            BuildError::Empty => f.write_str("no points to build a series from")
+        }
+    }
+}
+
+/// The first violation found by [`Series::check_invariants`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum InvariantError {
+    /// The bucket at `index` does not start after the bucket before it.
+    NotMonotonic { index: usize },
+    /// The bucket at `index` ends before it starts.
+    BackwardsRange { index: usize }
+}
+
+impl Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantError::NotMonotonic { index } => write!(f, "bucket {} does not start after the previous bucket", index),
+            InvariantError::BackwardsRange { index } => write!(f, "bucket {} ends before it starts", index)
+        }
+    }
+}
+
+/// The overall direction of a series, as classified by [`Series::trend`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Trend {
+    Flat,
+    Increasing,
+    Decreasing,
+    Mixed
+}
+
+/// What [`Series::would_merge`] predicts an append would do.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MergeOutcome {
+    /// The reading extends an existing bucket.
+    Merge,
+    /// The reading opens a new bucket.
+    NewBucket,
+    /// The reading is dropped, because it is not after the last reading or the series is full.
+    Reject
+}
+
+/// Why [`Series::merge`] refused to append a series.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MergeError {
+    /// The appended series does not start after this series ends.
+    Overlap,
+    /// Both series together hold more buckets than the capacity.
+    Capacity
+}
+
+impl Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::Overlap => write!(f, "series overlap"),
+            MergeError::Capacity => write!(f, "series do not fit in the capacity")
+        }
+    }
+}
+
+/// Concatenates two series with [`Series::merge`].
+///
+/// # Panics
+///
+/// Panics when `rhs` does not start after `self` ends or the buckets of both series do not
+/// fit in `N`. Use [`Series::merge`] to handle these cases.
+impl <const N : usize, I : Ord, T : Deviate> Add for Series<N, I, T> {
+    type Output = Series<N, I, T>;
+
+    fn add(mut self, rhs: Series<N, I, T>) -> Series<N, I, T> {
+        if let Err((error, _)) = self.merge(rhs) {
+            panic!("cannot concatenate series: {}", error)
+        }
+
+        self
+    }
+}
+
+fn min_max<I, T: PartialOrd>(buckets: &[SerieEntry<I, T>]) -> Option<(&T, &T)> {
+    let mut values = buckets.iter().map(|b| &b.value);
+    let first = values.next()?;
+
+    Some(values.fold((first, first), |(min, max), value| {
+        (if value < min { value } else { min }, if value > max { value } else { max })
+    }))
+}
+
+/// How [`Series::step_iter`] treats grid points that fall between two buckets.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GapMode {
+    /// Repeat the value of the preceding bucket.
+    Hold,
+    /// Yield nothing for the grid point.
+    Skip
+}
+
+/// How [`Series::value_at_with`] answers for a timestamp no bucket covers.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GapFill {
+    /// Yield nothing.
+    None,
+    /// Use the value of the preceding bucket, also after the last bucket.
+    HoldLast,
+    /// Use the value of the following bucket, also before the first bucket.
+    HoldNext,
+    /// Interpolate linearly from the end of the preceding bucket to the start of the following
+    /// one, see [`Interpolate`]. Yields nothing outside the series.
+    Linear
+}
+
+/// The time span covered by a bucket. A range either is a single point (`end == None`) or
+/// spans from `start` to `end`, where `end >= start` must always hold in the ordering of the
+/// series: `Ord`, or the comparator of [`Series::new_with_cmp`].
+#[derive(Debug, Eq, PartialEq)]
+pub struct Range<I> {
+    start: I,
+    end: Option<I>
+}
+
+impl <I: Ord + Sized> Range<I> {
+    pub fn new(start: I) -> Range<I> {
+        Range { start, end: None }
+    }
+
+    /// A range spanning from `start` to `end`. This does not check `end >= start`; a
+    /// backwards range is rejected by [`Series::from_buckets`].
+    pub fn ranged(start: I, end: I) -> Range<I> {
+        Range { start, end: Some(end) }
+    }
+
+    /// Extends the range to end at `value`, or leaves it unchanged and hands `value` back in
+    /// `Err` when `value <= start`, since that would produce a backwards range.
+    ///
+    /// This extends in place rather than taking and returning the range by value, so the
+    /// append path can extend the last bucket without moving it out of the series.
+    pub fn try_extend(&mut self, value: I) -> Result<(), I> {
+        self.try_extend_by(value, I::cmp)
+    }
+
+    /// Like [`Range::try_extend`], but orders timestamps with `cmp`, as for a series created
+    /// with [`Series::new_with_cmp`].
+    pub fn try_extend_by<F: FnOnce(&I, &I) -> Ordering>(&mut self, value: I, cmp: F) -> Result<(), I> {
+        if cmp(&value, &self.start).is_le() {
+            Err(value)
+        } else {
+            self.end = Some(value);
+            Ok(())
+        }
+    }
+
+    pub fn start(&self) -> &I {
+        &self.start
+    }
+
+    pub fn end(&self) -> Option<&I> {
+        self.end.as_ref()
+    }
+
+    /// The latest timestamp covered by the range.
+    pub fn latest(&self) -> &I {
+        self.end.as_ref().unwrap_or(&self.start)
+    }
+}
+
+pub trait Deviate {
+    fn deviate(&self, other: &Self, max_deviation: &Self) -> bool;
+
+    /// Whether two equal values can deviate from each other under `max_deviation`. Appending
+    /// only skips [`Deviate::deviate`] for a reading equal to the bucket's value when this is
+    /// `false`. Defaults to `true`, which always consults [`Deviate::deviate`].
+    fn equal_may_deviate(_max_deviation: &Self) -> bool where Self: Sized {
+        true
+    }
+
+    /// The magnitude of the difference between `self` and `other`, for telling how close a
+    /// reading came to deviating. Defaults to the absolute difference for ordered types with
+    /// subtraction.
+    ///
+    /// `distance(other) > max_deviation` matches [`Deviate::deviate`] only for symmetric
+    /// impls. The float impls are directional and only split on drops, so for `f32` and `f64`
+    /// the distance is not the merge criterion: a rise reports its full distance but still
+    /// merges.
+    fn distance(&self, other: &Self) -> Self where Self: Sized + Copy + PartialOrd + Sub<Output = Self> {
+        if self > other { *self - *other } else { *other - *self }
+    }
+
+    /// Clamps `self` to `[reference - max_deviation, reference + max_deviation]`, the band a
+    /// bucket with value `reference` stands for. A value that cannot be ordered against the
+    /// band, such as NaN, is returned unchanged.
+    fn clamp_within(&self, reference: &Self, max_deviation: &Self) -> Self
+        where Self: Sized + Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> {
+        let (low, high) = (*reference - *max_deviation, *reference + *max_deviation);
+
+        if *self < low { low } else if *self > high { high } else { *self }
+    }
+}
+
+/// A NaN on either side always deviates, so a NaN reading ends up isolated in its own
+/// single-point bucket instead of silently merging into its neighbors.
+impl Deviate for f32 {
+    fn deviate(&self, other: &Self, max_deviation: &Self) -> bool {
+        self.is_nan() || other.is_nan() || self - other > *max_deviation
+    }
+
+    fn equal_may_deviate(max_deviation: &Self) -> bool {
+        *max_deviation < 0.0
+    }
+
+    fn distance(&self, other: &Self) -> Self {
+        (self - other).abs()
+    }
+}
+
+/// A NaN on either side always deviates, so a NaN reading ends up isolated in its own
+/// single-point bucket instead of silently merging into its neighbors.
+impl Deviate for f64 {
+    fn deviate(&self, other: &Self, max_deviation: &Self) -> bool {
+        self.is_nan() || other.is_nan() || self - other > *max_deviation
+    }
+
+    fn equal_may_deviate(max_deviation: &Self) -> bool {
+        *max_deviation < 0.0
+    }
+
+    fn distance(&self, other: &Self) -> Self {
+        (self - other).abs()
+    }
+}
+
+/// Unlike the float impls this is symmetric: durations deviate when they differ by more than
+/// `max_deviation` in either direction.
+impl Deviate for Duration {
+    fn deviate(&self, other: &Self, max_deviation: &Self) -> bool {
+        self.distance(other) > *max_deviation
+    }
+
+    fn equal_may_deviate(_max_deviation: &Self) -> bool {
+        false
+    }
+
+    fn distance(&self, other: &Self) -> Self {
+        self.saturating_sub(*other).max(other.saturating_sub(*self))
+    }
+
+    fn clamp_within(&self, reference: &Self, max_deviation: &Self) -> Self {
+        (*self).clamp(reference.saturating_sub(*max_deviation), reference.saturating_add(*max_deviation))
+    }
+}
+
+/// Any change of level deviates and `max_deviation` is ignored, which turns a series of
+/// `bool` into a run-length encoding of a digital signal.
+impl Deviate for bool {
+    fn deviate(&self, other: &Self, _max_deviation: &Self) -> bool {
+        self != other
+    }
+
+    fn equal_may_deviate(_max_deviation: &Self) -> bool {
+        false
+    }
+}
+
+/// Deviates when either component deviates, each against its own threshold.
+impl <A : Deviate, B : Deviate> Deviate for (A, B) {
+    fn deviate(&self, other: &Self, max_deviation: &Self) -> bool {
+        self.0.deviate(&other.0, &max_deviation.0)
+            || self.1.deviate(&other.1, &max_deviation.1)
+    }
+
+    fn equal_may_deviate(max_deviation: &Self) -> bool {
+        A::equal_may_deviate(&max_deviation.0) || B::equal_may_deviate(&max_deviation.1)
+    }
+}
+
+impl <A : Deviate, B : Deviate, C : Deviate> Deviate for (A, B, C) {
+    fn deviate(&self, other: &Self, max_deviation: &Self) -> bool {
+        self.0.deviate(&other.0, &max_deviation.0)
+            || self.1.deviate(&other.1, &max_deviation.1)
+            || self.2.deviate(&other.2, &max_deviation.2)
+    }
+
+    fn equal_may_deviate(max_deviation: &Self) -> bool {
+        A::equal_may_deviate(&max_deviation.0) || B::equal_may_deviate(&max_deviation.1)
+            || C::equal_may_deviate(&max_deviation.2)
+    }
+}
+
+impl <A : Deviate, B : Deviate, C : Deviate, D : Deviate> Deviate for (A, B, C, D) {
+    fn deviate(&self, other: &Self, max_deviation: &Self) -> bool {
+        self.0.deviate(&other.0, &max_deviation.0)
+            || self.1.deviate(&other.1, &max_deviation.1)
+            || self.2.deviate(&other.2, &max_deviation.2)
+            || self.3.deviate(&other.3, &max_deviation.3)
+    }
+
+    fn equal_may_deviate(max_deviation: &Self) -> bool {
+        A::equal_may_deviate(&max_deviation.0) || B::equal_may_deviate(&max_deviation.1)
+            || C::equal_may_deviate(&max_deviation.2) || D::equal_may_deviate(&max_deviation.3)
+    }
+}
+
+pub trait Quantize {
+    /// Rounds to the nearest multiple of `quantum`, with halfway cases rounded away from zero.
+    fn quantize(&self, quantum: &Self) -> Self;
+}
+
+impl Quantize for f32 {
+    fn quantize(&self, quantum: &Self) -> Self {
+        let steps = self / quantum;
+
+        // Beyond 2^23 every f32 is already an integer, and NaN is passed through.
+        if steps.is_nan() || steps.abs() >= 8_388_608.0 {
+            return *self
+        }
+
+        let truncated = steps as i32 as f32;
+        let rounded = match steps - truncated {
+            frac if frac >= 0.5 => truncated + 1.0,
+            frac if frac <= -0.5 => truncated - 1.0,
+            _ => truncated
+        };
+
+        rounded * quantum
+    }
+}
+
+impl Quantize for f64 {
+    fn quantize(&self, quantum: &Self) -> Self {
+        let steps = self / quantum;
+
+        // Beyond 2^52 every f64 is already an integer, and NaN is passed through.
+        if steps.is_nan() || steps.abs() >= 4_503_599_627_370_496.0 {
+            return *self
+        }
+
+        let truncated = steps as i64 as f64;
+        let rounded = match steps - truncated {
+            frac if frac >= 0.5 => truncated + 1.0,
+            frac if frac <= -0.5 => truncated - 1.0,
+            _ => truncated
+        };
+
+        rounded * quantum
+    }
+}
+
+/// How [`Series::new_metric`] combines the differences of a multi-channel value.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Metric {
+    /// Deviates when the sum over all channels of the difference divided by that channel's
+    /// threshold exceeds one.
+    L1,
+    /// Deviates when any channel differs by more than its threshold.
+    Inf
+}
+
+pub trait DeviateL1 {
+    fn deviate_l1(&self, other: &Self, max_deviation: &Self) -> bool;
+}
+
+pub trait DeviateInf {
+    fn deviate_inf(&self, other: &Self, max_deviation: &Self) -> bool;
+}
+
+macro_rules! deviate_norm {
+    ($($t:ty),*) => {$(
+        /// A NaN in any channel on either side always deviates.
+        impl <const D : usize> DeviateL1 for [$t; D] {
+            fn deviate_l1(&self, other: &Self, max_deviation: &Self) -> bool {
+                if self.iter().chain(other).any(|channel| channel.is_nan()) {
+                    return true
+                }
+
+                // A channel that did not move contributes nothing, even against a zero threshold.
+                let scaled = self.iter().zip(other).zip(max_deviation).map(|((a, b), max)| match (a - b).abs() {
+                    0.0 => 0.0,
+                    difference => difference / max
+                });
+
+                scaled.sum::<$t>() > 1.0
+            }
+        }
+
+        /// A NaN in any channel on either side always deviates.
+        impl <const D : usize> DeviateInf for [$t; D] {
+            fn deviate_inf(&self, other: &Self, max_deviation: &Self) -> bool {
+                self.iter().zip(other).zip(max_deviation).any(|((a, b), max)| a.is_nan() || b.is_nan() || (a - b).abs() > *max)
+            }
+        }
+    )*}
+}
+
+deviate_norm!(f32, f64);
+
+/// The comparison of [`Series::new_relative`].
+pub trait DeviateRelative {
+    /// Whether `other` differs from `self` by more than `max_ratio` times the magnitude of
+    /// `self`.
+    fn deviate_relative(&self, other: &Self, max_ratio: &Self) -> bool;
+}
+
+/// The door arithmetic of [`Series::new_sdt`]. A door is the pair `(low, high)` of the values
+/// still within the threshold of every reading of a bucket.
+pub trait SwingingDoor : PartialEq + Sized {
+    /// Narrows `door` to the values that are also within `max_deviation` of `self`, or opens
+    /// a door around `self` when there is none. Returns `None` when no value is left.
+    fn narrow(&self, door: Option<&(Self, Self)>, max_deviation: &Self) -> Option<(Self, Self)>;
+
+    /// The value stored for a bucket with the given door.
+    fn midpoint(door: &(Self, Self)) -> Self;
+}
+
+macro_rules! deviate_float {
+    ($($t:ty),*) => {$(
+        /// A bucket value of zero makes every other reading deviate, and a NaN on either side
+        /// always deviates.
+        impl DeviateRelative for $t {
+            fn deviate_relative(&self, other: &Self, max_ratio: &Self) -> bool {
+                self.is_nan() || other.is_nan() || (self - other).abs() > max_ratio * self.abs()
+            }
+        }
+
+        /// A NaN reading never fits a door, so it ends up in its own single-point bucket.
+        impl SwingingDoor for $t {
+            fn narrow(&self, door: Option<&(Self, Self)>, max_deviation: &Self) -> Option<(Self, Self)> {
+                let (low, high) = (self - max_deviation, self + max_deviation);
+                let (low, high) = match door {
+                    Some(&(door_low, door_high)) => (low.max(door_low), high.min(door_high)),
+                    None => (low, high)
+                };
+
+                (low <= high && !self.is_nan()).then_some((low, high))
+            }
+
+            fn midpoint(door: &(Self, Self)) -> Self {
+                door.0 + (door.1 - door.0) / 2.0
+            }
+        }
+    )*}
+}
+
+deviate_float!(f32, f64);
+
+/// Scaling of values by timestamp differences, used by [`Series::slope_at`] and by
+/// [`GapFill::Linear`] on floats. Implemented for floats over the primitive integer timestamps,
+/// which are converted with `as`, and over [`Duration`] in seconds.
+pub trait Scale<I> : Sized {
+    /// `self` per `duration`, that is `self / duration`.
+    fn per(&self, duration: &I) -> Self;
+
+    /// `self` times the fraction `elapsed / duration`.
+    fn scale(&self, elapsed: &I, duration: &I) -> Self;
+}
+
+macro_rules! scale_float {
+    ($f:ty => $($i:ty),*) => {
+        $(impl Scale<$i> for $f {
+            fn per(&self, duration: &$i) -> Self {
+                self / *duration as $f
+            }
+
+            fn scale(&self, elapsed: &$i, duration: &$i) -> Self {
+                self * (*elapsed as $f / *duration as $f)
+            }
+        })*
+
+        impl Scale<Duration> for $f {
+            fn per(&self, duration: &Duration) -> Self {
+                self / duration.as_secs_f64() as $f
+            }
+
+            fn scale(&self, elapsed: &Duration, duration: &Duration) -> Self {
+                self * (elapsed.as_secs_f64() / duration.as_secs_f64()) as $f
+            }
+        }
+    }
+}
+
+scale_float!(f32 => u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+scale_float!(f64 => u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Linear interpolation for [`GapFill::Linear`], the only fill mode of
+/// [`Series::value_at_with`] that needs arithmetic on values. The default yields nothing, so a
+/// value type without meaningful interpolation, such as `bool`, opts in with an empty impl and
+/// can still use the other fill modes. Floats interpolate through [`Scale`].
+pub trait Interpolate<I> : Sized {
+    /// The value at `at` on the line from `from` to `to`, each a timestamp and a value.
+    fn interpolate(from: (&I, &Self), to: (&I, &Self), at: &I) -> Option<Self> {
+        let _ = (from, to, at);
+        None
+    }
+}
+
+macro_rules! interpolate_float {
+    ($($t:ty),*) => {$(
+        impl <I : Copy + Sub<Output = I>> Interpolate<I> for $t where $t: Scale<I> {
+            fn interpolate((from, previous): (&I, &Self), (to, next): (&I, &Self), at: &I) -> Option<Self> {
+                Some(previous + (next - previous).scale(&(*at - *from), &(*to - *from)))
+            }
+        }
+    )*}
+}
+
+interpolate_float!(f32, f64);
+
+macro_rules! no_interpolate {
+    ($($t:ty),*) => {$(
+        impl <I> Interpolate<I> for $t {}
+    )*}
+}
+
+no_interpolate!(bool, Duration, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl <I, T> Interpolate<I> for Option<T> {}
+impl <I, A, B> Interpolate<I> for (A, B) {}
+impl <I, A, B, C> Interpolate<I> for (A, B, C) {}
+impl <I, A, B, C, D> Interpolate<I> for (A, B, C, D) {}
+impl <I, T, const D : usize> Interpolate<I> for [T; D] {}
+
+/// Arithmetic that reports overflow and division by zero as `None` instead of panicking, used
+/// by [`Series::checked_integral`] and [`Series::checked_mean`].
+pub trait CheckedArith : Sized {
+    const ZERO: Self;
+
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+    fn checked_div(&self, other: &Self) -> Option<Self>;
+}
+
+macro_rules! checked_arith {
+    ($($t:ty),*) => {$(
+        impl CheckedArith for $t {
+            const ZERO: Self = 0;
+
+            fn checked_add(&self, other: &Self) -> Option<Self> {
+                <$t>::checked_add(*self, *other)
+            }
+
+            fn checked_mul(&self, other: &Self) -> Option<Self> {
+                <$t>::checked_mul(*self, *other)
+            }
+
+            fn checked_div(&self, other: &Self) -> Option<Self> {
+                <$t>::checked_div(*self, *other)
+            }
+        }
+    )*}
+}
+
+checked_arith!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+/// Models nullable readings: a transition between `None` and `Some` always deviates, two
+/// `Some` values use the inner [`Deviate`], and consecutive `None`s merge. A `None` threshold
+/// makes every reading deviate.
+impl <T : Deviate> Deviate for Option<T> {
+    fn deviate(&self, other: &Self, max_deviation: &Self) -> bool {
+        match (self, other, max_deviation) {
+            (Some(a), Some(b), Some(max)) => a.deviate(b, max),
+            (None, None, Some(_)) => false,
+            _ => true
+        }
+    }
+
+    /// A `None` threshold splits even equal readings.
+    fn equal_may_deviate(max_deviation: &Self) -> bool {
+        max_deviation.as_ref().map(T::equal_may_deviate).unwrap_or(true)
+    }
+}
+
+/// Deviation with separate thresholds for readings above and below a reference value.
+pub trait DeviateAsym {
+    /// Returns `true` if `self` lies more than `up` above, or more than `down` below, `other`.
+    fn deviate_asym(&self, other: &Self, up: &Self, down: &Self) -> bool;
+}
+
+/// Composes the directional [`Deviate`] in both directions, which for `f32`/`f64` is exactly
+/// `self - other > up || other - self > down`.
+impl <T : Deviate> DeviateAsym for T {
+    fn deviate_asym(&self, other: &Self, up: &Self, down: &Self) -> bool {
+        self.deviate(other, up) || other.deviate(self, down)
+    }
+}
+
+/// Deviates when any element deviates from its counterpart, using the element at the same
+/// position in `max_deviation` as that axis' threshold.
+impl <T : Deviate, const D : usize> Deviate for [T; D] {
+    fn deviate(&self, other: &Self, max_deviation: &Self) -> bool {
+        self.iter().zip(other).zip(max_deviation).any(|((a, b), max)| a.deviate(b, max))
+    }
+
+    fn equal_may_deviate(max_deviation: &Self) -> bool {
+        max_deviation.iter().any(T::equal_may_deviate)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct SerieEntry<I, T> {
+    range: Range<I>,
+    value: T
+}
+
+impl <I, T> SerieEntry<I, T> {
+    /// Creates a bucket, for example to rebuild a series with [`Series::from_buckets`].
+    pub fn new(range: Range<I>, value: T) -> SerieEntry<I, T> {
+        SerieEntry { range, value }
+    }
+
+    pub fn range(&self) -> &Range<I> {
+        &self.range
+    }
+
+    pub fn start(&self) -> &I {
+        &self.range.start
+    }
+
+    pub fn end(&self) -> Option<&I> {
+        self.range.end.as_ref()
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// The timestamp halfway through the bucket, or its `start` when it is a single point.
+    pub fn midpoint_time(&self) -> I where I: Copy + Add<Output = I> + Sub<Output = I> + Div<Output = I> + From<u8> {
+        match self.range.end {
+            Some(end) => self.range.start + (end - self.range.start) / I::from(2),
+            None => self.range.start
+        }
+    }
+
+    /// The time spanned by the bucket, or `None` when it is a single point.
+    pub fn duration(&self) -> Option<I> where I: Copy + Sub<Output = I> {
+        self.range.end.map(|end| end - self.range.start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 3_u8, 32.5f32));
+        assert!(timeseries.append_monotonic( 4_u8, 33.8f32));
+        assert!(timeseries.append_monotonic( 6_u8, 34.0f32));
+        assert!(timeseries.append_monotonic( 8_u8, 28.2f32));
+        assert!(timeseries.append_monotonic( 10_u8, 12.3f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(6) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 8, end: None }, value: 28.2 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 10, end: None }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn should_not_append_when_full() {
+        let mut timeseries: Series<1, u8, f32> = Series::new(0.3);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(!timeseries.append_monotonic( 2_u8, 32.7f32));
+        assert!(timeseries.is_full());
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: None }, value: 32.6 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+
+    #[test]
+    fn should_return_false_when_append_is_not_monotonic_with_end() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 6_u8, 32.7f32));
+        assert!(!timeseries.append_monotonic( 1_u8, 32.5f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(6) }, value: 32.6 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn should_return_false_when_append_is_not_monotonic_without_end() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 6_u8, 35.7f32));
+        assert!(!timeseries.append_monotonic( 1_u8, 32.5f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(6) }, value: 32.6 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn starts_at_some() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 32.7f32));
+
+        assert_eq!(timeseries.starts_at(), Some(&1_u8));
+    }
+
+    #[test]
+    fn starts_at_none() {
+        let timeseries: Series<1, u8, f32> = Series::new(0.3f32);
+        assert_eq!(timeseries.starts_at(), None);
+    }
+
+    #[test]
+    fn ends_at_some_range_end() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 32.7f32));
+
+        assert_eq!(timeseries.ends_at(), Some(&2_u8));
+    }
+
+    #[test]
+    fn ends_at_some_range_start() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 35.7f32));
+
+        assert_eq!(timeseries.ends_at(), Some(&2_u8));
+    }
+
+    #[test]
+    fn ends_at_none() {
+        let timeseries: Series<1, u8, f32> = Series::new(0.3f32);
+        assert_eq!(timeseries.ends_at(), None);
+    }
+
+    #[test]
+    fn close_single_point_bucket() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        timeseries.close();
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(1) }, value: 32.6 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn append_after_close_starts_new_bucket() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        timeseries.close();
+        assert!(timeseries.append_monotonic( 2_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 3_u8, 32.6f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(1) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 2, end: Some(3) }, value: 32.6 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn append_after_close_respects_capacity() {
+        let mut timeseries: Series<1, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        timeseries.close();
+
+        assert!(!timeseries.append_monotonic( 2_u8, 32.6f32));
+        assert_eq!(timeseries.ends_at(), Some(&1));
+    }
+
+    #[test]
+    fn close_leaves_ranged_bucket_unchanged() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_u8, 32.7f32));
+        timeseries.close();
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(4) }, value: 32.6 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn step_iter_stride_one() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 10.0f32));
+        assert!(timeseries.append_monotonic( 3_u8, 10.1f32));
+        assert!(timeseries.append_monotonic( 6_u8, 5.0f32));
+
+        let held: Vec<(u8, f32), 10> = timeseries.step_iter(1, GapMode::Hold).collect();
+        let skipped: Vec<(u8, f32), 10> = timeseries.step_iter(1, GapMode::Skip).collect();
+
+        assert_eq!(held, [(1, 10.0), (2, 10.0), (3, 10.0), (4, 10.0), (5, 10.0), (6, 5.0)]);
+        assert_eq!(skipped, [(1, 10.0), (2, 10.0), (3, 10.0), (6, 5.0)]);
+    }
+
+    #[test]
+    fn step_iter_stride_two() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 10.0f32));
+        assert!(timeseries.append_monotonic( 3_u8, 10.1f32));
+        assert!(timeseries.append_monotonic( 7_u8, 5.0f32));
+
+        let held: Vec<(u8, f32), 10> = timeseries.step_iter(2, GapMode::Hold).collect();
+        let skipped: Vec<(u8, f32), 10> = timeseries.step_iter(2, GapMode::Skip).collect();
+
+        assert_eq!(held, [(1, 10.0), (3, 10.0), (5, 10.0), (7, 5.0)]);
+        assert_eq!(skipped, [(1, 10.0), (3, 10.0), (7, 5.0)]);
+    }
+
+    #[test]
+    fn step_iter_zero_stride_is_empty() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 10.0f32));
+
+        assert_eq!(timeseries.step_iter(0, GapMode::Hold).count(), 0);
+    }
+
+    #[test]
+    fn step_iter_negative_step_yields_nothing() {
+        let mut timeseries: Series<10, i8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_i8, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_i8, 32.6f32));
+
+        assert_eq!(timeseries.step_iter(-1, GapMode::Hold).count(), 0);
+    }
+
+    #[test]
+    fn shift_timestamps_forward() {
+        let mut timeseries: Series<10, i32, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_i32, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_i32, 32.7f32));
+        assert!(timeseries.append_monotonic( 6_i32, 12.3f32));
+        timeseries.shift_timestamps(10);
+
+        let mut expected: Vec<SerieEntry<i32, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 11, end: Some(14) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 16, end: None }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected);
+        assert_eq!(timeseries.starts_at(), Some(&11));
+    }
+
+    #[test]
+    fn shift_timestamps_backward() {
+        let mut timeseries: Series<10, i32, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_i32, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_i32, 32.7f32));
+        timeseries.shift_timestamps(-5);
+
+        assert_eq!(timeseries.starts_at(), Some(&-4));
+        assert_eq!(timeseries.ends_at(), Some(&-1));
+    }
+
+    #[test]
+    fn try_extend_rejects_backwards_range() {
+        let mut range = Range::new(5_u8);
+
+        assert_eq!(range.try_extend(3), Err(3));
+        assert_eq!(range.try_extend(5), Err(5));
+        assert_eq!(range, Range { start: 5, end: None });
+    }
+
+    #[test]
+    fn try_extend_by_uses_comparator() {
+        let mut range = Range::new(65500u16);
+
+        assert_eq!(range.try_extend_by(3, wrapping_cmp), Ok(()));
+        assert_eq!(range.end(), Some(&3));
+        assert_eq!(range.try_extend_by(65000, wrapping_cmp), Err(65000));
+        assert_eq!(range.end(), Some(&3));
+    }
+
+    #[test]
+    fn try_extend_at_type_maximum() {
+        let mut range = Range::new(1_u8);
+
+        assert_eq!(range.try_extend(u8::MAX), Ok(()));
+        assert_eq!(range, Range { start: 1, end: Some(u8::MAX) });
+    }
+
+    #[test]
+    fn append_extends_to_type_maximum() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 254_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( u8::MAX, 32.7f32));
+        assert!(!timeseries.append_monotonic( u8::MAX, 32.7f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 254, end: Some(u8::MAX) }, value: 32.6 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn array_deviates_when_any_axis_deviates() {
+        assert!(![1.0f32, 2.0, 3.0].deviate(&[0.9, 1.9, 2.9], &[0.3, 0.3, 0.3]));
+        assert!([1.0f32, 2.0, 3.0].deviate(&[0.9, 1.0, 2.9], &[0.3, 0.3, 0.3]));
+        assert!(![1.0f32, 2.0, 3.0].deviate(&[0.9, 1.0, 2.9], &[0.3, 1.5, 0.3]));
+    }
+
+    #[test]
+    fn array_series_splits_on_single_axis() {
+        let mut timeseries: Series<10, u32, [f32; 3]> = Series::new([0.3, 0.3, 0.3]);
+
+        assert!(timeseries.append_monotonic( 1_u32, [1.0, 2.0, 3.0]));
+        assert!(timeseries.append_monotonic( 2_u32, [1.1, 1.9, 3.1]));
+        assert!(timeseries.append_monotonic( 3_u32, [1.0, 2.0, 2.0]));
+
+        let mut expected: Vec<SerieEntry<u32, [f32; 3]>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: [1.0, 2.0, 3.0] }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 3, end: None }, value: [1.0, 2.0, 2.0] }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn merge_adjacent_collapses_within_threshold() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 32.6 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 4, end: None }, value: 32.5 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: None }, value: 32.7 }).unwrap();
+        timeseries.merge_adjacent(None);
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(6) }, value: 32.6 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn merge_adjacent_keeps_deviating_neighbors() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: None }, value: 32.6 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 4, end: Some(5) }, value: 32.5 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: None }, value: 12.3 }).unwrap();
+        timeseries.merge_adjacent(None);
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(5) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 6, end: None }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn merge_adjacent_respects_max_gap() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 32.6 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 4, end: None }, value: 32.5 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 10, end: None }, value: 32.7 }).unwrap();
+        timeseries.merge_adjacent(Some(2));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(4) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 10, end: None }, value: 32.7 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn deviate_asym_uses_separate_thresholds() {
+        assert!(33.0f32.deviate_asym(&32.0, &0.5, &2.0));
+        assert!(!31.0f32.deviate_asym(&32.0, &0.5, &2.0));
+        assert!(29.0f64.deviate_asym(&32.0, &0.5, &2.0));
+        assert!(!32.4f64.deviate_asym(&32.0, &0.5, &2.0));
+    }
+
+    #[test]
+    fn asym_series_splits_on_spike_and_merges_on_dip() {
+        let mut timeseries: Series<10, u8, f32> = Series::new_asym(0.5f32, 2.0f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.0f32));
+        assert!(timeseries.append_monotonic( 2_u8, 31.0f32));
+        assert!(timeseries.append_monotonic( 3_u8, 33.0f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 32.0 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 3, end: None }, value: 33.0 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn last_bucket_none() {
+        let timeseries: Series<1, u8, f32> = Series::new(0.3f32);
+        assert_eq!(timeseries.last_bucket(), None);
+    }
+
+    #[test]
+    fn last_bucket_after_merge() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 32.7f32));
+
+        assert_eq!(timeseries.last_bucket(), Some(&SerieEntry { range: Range { start: 1, end: Some(2) }, value: 32.6 }));
+    }
+
+    #[test]
+    fn last_bucket_after_split() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 12.3f32));
+
+        assert_eq!(timeseries.last_bucket(), Some(&SerieEntry { range: Range { start: 2, end: None }, value: 12.3 }));
+    }
+
+    #[test]
+    fn gaps_single() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 5_u8, 12.3f32));
+
+        let gaps: Vec<(u8, u8), 4> = timeseries.gaps().collect();
+
+        assert_eq!(gaps, [(2, 5)]);
+    }
+
+    #[test]
+    fn gaps_multiple() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_u8, 12.3f32));
+        assert!(timeseries.append_monotonic( 5_u8, 2.3f32));
+        assert!(timeseries.append_monotonic( 9_u8, 0.3f32));
+
+        let gaps: Vec<(u8, u8), 4> = timeseries.gaps().collect();
+
+        assert_eq!(gaps, [(1, 4), (5, 9)]);
+    }
+
+    #[test]
+    fn gaps_none() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert_eq!(timeseries.gaps().count(), 0);
+
+        assert!(timeseries.append_monotonic( 2_u8, 12.3f32));
+        assert!(timeseries.append_monotonic( 3_u8, 2.3f32));
+        assert_eq!(timeseries.gaps().count(), 0);
+    }
+
+    #[test]
+    fn write_lines_one_per_bucket() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.5f32));
+        assert!(timeseries.append_monotonic( 2_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 5_u8, 12.25f32));
+
+        let mut out = std::string::String::new();
+        timeseries.write_lines(&mut out).unwrap();
+
+        assert_eq!(out, "1,2,32.5\n5,,12.25\n");
+    }
+
+    #[test]
+    fn resample_uniform_five_points() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 10.0f32));
+        assert!(timeseries.append_monotonic( 3_u8, 10.1f32));
+        assert!(timeseries.append_monotonic( 6_u8, 5.0f32));
+
+        let resampled: Series<5, u8, f32> = timeseries.resample_uniform(2, 1, 5, 0.0);
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 2, end: Some(5) }, value: 10.0 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 6, end: None }, value: 5.0 }).unwrap();
+
+        assert_eq!(resampled.buckets, expected)
+    }
+
+    #[test]
+    fn resample_uniform_stops_past_covered_range() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 10.0f32));
+        assert!(timeseries.append_monotonic( 3_u8, 10.1f32));
+        assert!(timeseries.append_monotonic( 6_u8, 5.0f32));
+
+        let resampled: Series<5, u8, f32> = timeseries.resample_uniform(0, 2, 5, 0.0);
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 2, end: Some(4) }, value: 10.0 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 6, end: None }, value: 5.0 }).unwrap();
+
+        assert_eq!(resampled.buckets, expected)
+    }
+
+    #[test]
+    fn resample_uniform_near_type_maximum() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 10.0f32));
+        assert!(timeseries.append_monotonic( 250_u8, 5.0f32));
+
+        let resampled: Series<10, u8, f32> = timeseries.resample_uniform(1, 10, 40, 0.0);
+
+        assert_eq!(resampled.starts_at(), Some(&1));
+        assert_eq!(resampled.ends_at(), Some(&241));
+        assert_eq!(resampled.len(), 1);
+    }
+
+    #[test]
+    fn envelope_near_type_maximum() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 10.0f32));
+        assert!(timeseries.append_monotonic( 250_u8, 5.0f32));
+
+        let envelope: Vec<(u8, f32, f32), 4> = timeseries.envelope(100);
+
+        assert_eq!(envelope.as_slice(), &[(1, 10.0, 10.0), (201, 5.0, 5.0)]);
+    }
+
+    #[test]
+    fn extend_from_drains_staging_series() {
+        let mut archive: Series<10, u8, f32> = Series::new(0.3f32);
+        let mut staging: Series<4, u8, f32> = Series::new(0.3f32);
+
+        assert!(archive.append_monotonic( 1_u8, 32.6f32));
+        assert!(staging.append_monotonic( 3_u8, 22.6f32));
+        assert!(staging.append_monotonic( 4_u8, 12.6f32));
+
+        assert_eq!(archive.extend_from(staging), Ok(()));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: None }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 3, end: None }, value: 22.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 4, end: None }, value: 12.6 }).unwrap();
+
+        assert_eq!(archive.buckets, expected)
+    }
+
+    #[test]
+    fn extend_from_returns_remainder_when_full() {
+        let mut archive: Series<2, u8, f32> = Series::new(0.3f32);
+        let mut staging: Series<4, u8, f32> = Series::new(0.3f32);
+
+        assert!(archive.append_monotonic( 1_u8, 32.6f32));
+        assert!(staging.append_monotonic( 3_u8, 22.6f32));
+        assert!(staging.append_monotonic( 4_u8, 12.6f32));
+        assert!(staging.append_monotonic( 5_u8, 2.6f32));
+
+        let (appended, remainder) = archive.extend_from(staging).unwrap_err();
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 4> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 4, end: None }, value: 12.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 5, end: None }, value: 2.6 }).unwrap();
+
+        assert_eq!(appended, 1);
+        assert_eq!(archive.ends_at(), Some(&3));
+        assert_eq!(remainder.buckets, expected)
+    }
+
+    #[test]
+    fn extend_from_rejects_overlap() {
+        let mut archive: Series<10, u8, f32> = Series::new(0.3f32);
+        let mut staging: Series<4, u8, f32> = Series::new(0.3f32);
+
+        assert!(archive.append_monotonic( 1_u8, 32.6f32));
+        assert!(archive.append_monotonic( 3_u8, 32.6f32));
+        assert!(staging.append_monotonic( 3_u8, 22.6f32));
+
+        let (appended, remainder) = archive.extend_from(staging).unwrap_err();
+
+        assert_eq!(appended, 0);
+        assert_eq!(remainder.starts_at(), Some(&3));
+        assert_eq!(archive.buckets.len(), 1);
+    }
+
+    #[test]
+    fn approx_eq_within_tolerance() {
+        let mut a: Series<10, u8, f32> = Series::new(0.3f32);
+        let mut b: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(a.append_monotonic( 1_u8, 32.6f32));
+        assert!(a.append_monotonic( 2_u8, 12.3f32));
+        assert!(b.append_monotonic( 1_u8, 32.61f32));
+        assert!(b.append_monotonic( 2_u8, 12.29f32));
+
+        assert!(a.approx_eq(&b, &0.05));
+        assert!(b.approx_eq(&a, &0.05));
+    }
+
+    #[test]
+    fn approx_eq_beyond_tolerance() {
+        let mut a: Series<10, u8, f32> = Series::new(0.3f32);
+        let mut b: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(a.append_monotonic( 1_u8, 32.6f32));
+        assert!(a.append_monotonic( 2_u8, 12.3f32));
+        assert!(b.append_monotonic( 1_u8, 32.6f32));
+        assert!(b.append_monotonic( 2_u8, 12.5f32));
+
+        assert!(!a.approx_eq(&b, &0.05));
+        assert!(!b.approx_eq(&a, &0.05));
+    }
+
+    #[test]
+    fn approx_eq_requires_equal_ranges() {
+        let mut a: Series<10, u8, f32> = Series::new(0.3f32);
+        let mut b: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(a.append_monotonic( 1_u8, 32.6f32));
+        assert!(a.append_monotonic( 2_u8, 32.6f32));
+        assert!(b.append_monotonic( 1_u8, 32.6f32));
+
+        assert!(!a.approx_eq(&b, &0.05));
+    }
+
+    #[test]
+    fn dedup_flat_collapses_equal_run() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 5.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 3, end: None }, value: 5.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 4, end: None }, value: 5.0 }).unwrap();
+        timeseries.dedup_flat();
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(4) }, value: 5.0 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn dedup_flat_keeps_differing_neighbors() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: None }, value: 5.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 3, end: Some(4) }, value: 5.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 5, end: None }, value: 6.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: None }, value: 5.0 }).unwrap();
+        timeseries.dedup_flat();
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(4) }, value: 5.0 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 5, end: None }, value: 6.0 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 6, end: None }, value: 5.0 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn value_range_some() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 20.0f32));
+        assert!(timeseries.append_monotonic( 2_u8, 5.0f32));
+        assert!(timeseries.append_monotonic( 3_u8, 4.5f32));
+        assert!(timeseries.append_monotonic( 4_u8, 2.0f32));
+        timeseries.buckets.push(SerieEntry { range: Range { start: 5, end: None }, value: 30.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: None }, value: 10.0 }).unwrap();
+
+        assert_eq!(timeseries.value_range(), Some((2.0, 30.0)));
+    }
+
+    #[test]
+    fn value_range_none() {
+        let timeseries: Series<1, u8, f32> = Series::new(0.3f32);
+        assert_eq!(timeseries.value_range(), None);
+    }
+
+    #[test]
+    fn nearest_value_in_bucket() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 2_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 8_u8, 12.3f32));
+
+        assert_eq!(timeseries.nearest_value(&2), Some(&32.6));
+        assert_eq!(timeseries.nearest_value(&3), Some(&32.6));
+        assert_eq!(timeseries.nearest_value(&8), Some(&12.3));
+    }
+
+    #[test]
+    fn nearest_value_in_gap() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 2_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 8_u8, 12.3f32));
+
+        assert_eq!(timeseries.nearest_value(&6), Some(&32.6));
+        assert_eq!(timeseries.nearest_value(&20), Some(&12.3));
+    }
+
+    #[test]
+    fn nearest_value_before_start() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert_eq!(timeseries.nearest_value(&1), None);
+
+        assert!(timeseries.append_monotonic( 2_u8, 32.6f32));
+
+        assert_eq!(timeseries.nearest_value(&1), None);
+    }
+
+    #[test]
+    fn resize_grows() {
+        let mut timeseries: Series<2, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 12.3f32));
+
+        let mut resized: Series<10, u8, f32> = timeseries.resize().unwrap();
+
+        assert!(resized.append_monotonic( 3_u8, 2.3f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: None }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 2, end: None }, value: 12.3 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 3, end: None }, value: 2.3 }).unwrap();
+
+        assert_eq!(resized.buckets, expected)
+    }
+
+    #[test]
+    fn resize_shrink_fails_with_original() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 12.3f32));
+
+        let original = timeseries.resize::<1>().unwrap_err();
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: None }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 2, end: None }, value: 12.3 }).unwrap();
+
+        assert_eq!(original.buckets, expected)
+    }
+
+    #[test]
+    fn option_deviates_on_presence_change() {
+        assert!(Some(32.6f32).deviate(&None, &Some(0.3)));
+        assert!(None.deviate(&Some(32.6f32), &Some(0.3)));
+        assert!(!None::<f32>.deviate(&None, &Some(0.3)));
+        assert!(!Some(32.6f32).deviate(&Some(32.5), &Some(0.3)));
+        assert!(Some(32.6f32).deviate(&Some(32.5), &None));
+    }
+
+    #[test]
+    fn option_series_splits_on_missing_reading() {
+        let mut timeseries: Series<10, u8, Option<f32>> = Series::new(Some(0.3f32));
+
+        assert!(timeseries.append_monotonic( 1_u8, Some(32.6f32)));
+        assert!(timeseries.append_monotonic( 2_u8, Some(32.5f32)));
+        assert!(timeseries.append_monotonic( 3_u8, None));
+        assert!(timeseries.append_monotonic( 4_u8, None));
+        assert!(timeseries.append_monotonic( 5_u8, Some(32.6f32)));
+
+        let mut expected: Vec<SerieEntry<u8, Option<f32>>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: Some(32.6) }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 3, end: Some(4) }, value: None }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 5, end: None }, value: Some(32.6) }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn first_and_last_point_none() {
+        let timeseries: Series<1, u8, f32> = Series::new(0.3f32);
+
+        assert_eq!(timeseries.first_point(), None);
+        assert_eq!(timeseries.last_point(), None);
+    }
+
+    #[test]
+    fn first_and_last_point_single() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+
+        assert_eq!(timeseries.first_point(), Some((&1, &32.6)));
+        assert_eq!(timeseries.last_point(), Some((&1, &32.6)));
+    }
+
+    #[test]
+    fn first_and_last_point_ranged_last() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 12.3f32));
+        assert!(timeseries.append_monotonic( 5_u8, 12.4f32));
+
+        assert_eq!(timeseries.first_point(), Some((&1, &32.6)));
+        assert_eq!(timeseries.last_point(), Some((&5, &12.3)));
+    }
+
+    #[test]
+    fn ends_at_governed_by_last_bucket() {
+        let mut timeseries: Series<100, u8, f32> = Series::new(0.3f32);
+
+        for i in 0..50_u8 {
+            assert!(timeseries.append_monotonic( i * 2, 100.0 - f32::from(i)));
+            assert!(timeseries.append_monotonic( i * 2 + 1, 100.0 - f32::from(i)));
+        }
+
+        assert!(timeseries.append_monotonic( 200_u8, 0.0f32));
+
+        assert_eq!(timeseries.buckets.len(), 51);
+        assert_eq!(timeseries.ends_at(), Some(&200));
+    }
+
+    #[test]
+    fn split_bucket_interior() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 6_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 8_u8, 12.3f32));
+        assert!(timeseries.split_bucket(0, 4));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(4) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 4, end: Some(6) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 8, end: None }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn split_bucket_rejects_boundary() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 6_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 8_u8, 12.3f32));
+
+        assert!(!timeseries.split_bucket(0, 1));
+        assert!(!timeseries.split_bucket(0, 6));
+        assert!(!timeseries.split_bucket(1, 8));
+        assert!(!timeseries.split_bucket(2, 4));
+        assert_eq!(timeseries.buckets.len(), 2);
+    }
+
+    #[test]
+    fn split_bucket_rejects_when_full() {
+        let mut timeseries: Series<1, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(6) }, value: 32.6 }).unwrap();
+
+        assert!(!timeseries.split_bucket(0, 4));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 1> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(6) }, value: 32.6 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn quantize_to_nearest_multiple() {
+        assert_eq!(1.02f32.quantize(&0.5), 1.0);
+        assert_eq!(1.26f32.quantize(&0.5), 1.5);
+        assert_eq!((-1.26f32).quantize(&0.5), -1.5);
+        assert_eq!(0.75f64.quantize(&0.5), 1.0);
+        assert_eq!((-0.2f64).quantize(&0.5), 0.0);
+        assert!(f32::NAN.quantize(&0.5).is_nan());
+    }
+
+    #[test]
+    fn append_quantized_merges_noisy_readings() {
+        let mut quantized: Series<10, u8, f32> = Series::new(0.0f32);
+        let mut raw: Series<10, u8, f32> = Series::new(0.0f32);
+
+        assert!(quantized.append_quantized( 1_u8, 1.02f32, &0.5));
+        assert!(quantized.append_quantized( 2_u8, 0.98f32, &0.5));
+        assert!(raw.append_monotonic( 1_u8, 1.02f32));
+        assert!(raw.append_monotonic( 2_u8, 0.98f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 1.0 }).unwrap();
+
+        assert_eq!(quantized.buckets, expected);
+        assert_eq!(raw.buckets.len(), 2);
+    }
+
+    #[test]
+    fn entry_midpoint_and_duration_ranged() {
+        let entry = SerieEntry { range: Range { start: 250_u8, end: Some(255) }, value: 32.6f32 };
+
+        assert_eq!(entry.start(), &250);
+        assert_eq!(entry.end(), Some(&255));
+        assert_eq!(entry.value(), &32.6);
+        assert_eq!(entry.midpoint_time(), 252);
+        assert_eq!(entry.duration(), Some(5));
+    }
+
+    #[test]
+    fn entry_midpoint_and_duration_single_point() {
+        let entry = SerieEntry { range: Range { start: 7_u32, end: None }, value: 32.6f32 };
+
+        assert_eq!(entry.midpoint_time(), 7);
+        assert_eq!(entry.duration(), None);
+    }
+
+    #[test]
+    fn drain_before_splits_into_disjoint_sets() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 4_u8, 22.6f32));
+        assert!(timeseries.append_monotonic( 6_u8, 12.6f32));
+        assert!(timeseries.append_monotonic( 9_u8, 12.7f32));
+
+        let drained = timeseries.drain_before(&7);
+
+        let mut expected_drained: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected_drained.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 32.6 }).unwrap();
+        expected_drained.push(SerieEntry { range: Range { start: 4, end: None }, value: 22.6 }).unwrap();
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 6, end: Some(9) }, value: 12.6 }).unwrap();
+
+        assert_eq!(drained, expected_drained);
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn drain_before_excludes_bucket_ending_at() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_u8, 22.6f32));
+
+        assert_eq!(timeseries.drain_before(&4).len(), 1);
+        assert_eq!(timeseries.drain_before(&4).len(), 0);
+        assert_eq!(timeseries.starts_at(), Some(&4));
+    }
+
+    #[test]
+    fn peak_to_peak_over_window() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 30.0f32));
+        assert!(timeseries.append_monotonic( 3_u8, 20.0f32));
+        assert!(timeseries.append_monotonic( 5_u8, 10.0f32));
+        timeseries.buckets.push(SerieEntry { range: Range { start: 7, end: Some(9) }, value: 40.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 11, end: None }, value: 0.0 }).unwrap();
+
+        assert_eq!(timeseries.peak_to_peak(&3, &8), Some(30.0));
+        assert_eq!(timeseries.peak_to_peak(&9, &9), Some(0.0));
+        assert_eq!(timeseries.peak_to_peak(&0, &20), Some(40.0));
+    }
+
+    #[test]
+    fn peak_to_peak_no_overlap() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert_eq!(timeseries.peak_to_peak(&0, &20), None);
+
+        assert!(timeseries.append_monotonic( 1_u8, 30.0f32));
+        assert!(timeseries.append_monotonic( 5_u8, 10.0f32));
+
+        assert_eq!(timeseries.peak_to_peak(&2, &4), None);
+        assert_eq!(timeseries.peak_to_peak(&6, &9), None);
+    }
+
+    #[test]
+    fn upsert_replaces_value_at_same_timestamp() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.upsert( 1_u8, 32.6f32));
+        assert!(timeseries.upsert( 2_u8, 12.3f32));
+        assert!(timeseries.upsert( 2_u8, 2.3f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: None }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 2, end: None }, value: 2.3 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn upsert_merges_corrected_value_into_prior_bucket() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.upsert( 1_u8, 32.6f32));
+        assert!(timeseries.upsert( 2_u8, 32.7f32));
+        assert!(timeseries.upsert( 4_u8, 12.3f32));
+        assert!(timeseries.upsert( 4_u8, 32.5f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(4) }, value: 32.6 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn upsert_corrects_only_last_reading_of_ranged_bucket() {
+        let mut timeseries: Series<2, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.upsert( 1_u8, 10.0f32));
+        assert!(timeseries.upsert( 5_u8, 10.0f32));
+        assert!(timeseries.upsert( 5_u8, 10.1f32));
+        assert!(timeseries.upsert( 5_u8, 2.0f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 2> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(5) }, value: 10.0 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 5, end: None }, value: 2.0 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected);
+        assert_eq!(timeseries.value_at(&4), Some(&10.0));
+        assert_eq!(timeseries.value_at(&5), Some(&2.0));
+        assert_eq!(timeseries.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn upsert_split_needs_capacity() {
+        let mut timeseries: Series<2, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: None }, value: 20.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 3, end: Some(5) }, value: 10.0 }).unwrap();
+
+        assert!(!timeseries.upsert( 5_u8, 2.0f32));
+
+        assert_eq!(timeseries.value_at(&5), Some(&10.0));
+    }
+
+    #[test]
+    fn upsert_merge_respects_max_span() {
+        let mut timeseries: Series<10, u8, f32> = Series::new_with_max_span(0.3f32, 3);
+
+        assert!(timeseries.upsert( 0_u8, 10.0f32));
+        assert!(timeseries.upsert( 3_u8, 10.0f32));
+        assert!(timeseries.upsert( 6_u8, 2.0f32));
+        assert!(timeseries.upsert( 6_u8, 10.0f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 2> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 0, end: Some(3) }, value: 10.0 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 6, end: None }, value: 10.0 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected);
+    }
+
+    #[test]
+    fn upsert_rejects_earlier_timestamp() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.upsert( 1_u8, 32.6f32));
+        assert!(timeseries.upsert( 4_u8, 12.3f32));
+        assert!(!timeseries.upsert( 3_u8, 2.3f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: None }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 4, end: None }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn compress_slice_all_fit() {
+        let points = [(1_u8, 32.6f32), (2, 32.7), (3, 12.3), (4, 12.4)];
+        let (timeseries, consumed): (Series<3, u8, f32>, usize) = Series::compress_slice(0.3f32, &points);
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 3, end: Some(4) }, value: 12.3 }).unwrap();
+
+        assert_eq!(consumed, points.len());
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn compress_slice_partial_fit() {
+        let points = [(1_u8, 32.6f32), (2, 12.3), (3, 2.3), (4, 0.3)];
+        let (timeseries, consumed): (Series<2, u8, f32>, usize) = Series::compress_slice(0.3f32, &points);
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: None }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 2, end: None }, value: 12.3 }).unwrap();
+
+        assert_eq!(consumed, 2);
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn buckets_above_and_below_threshold() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 10.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 3, end: None }, value: 30.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 4, end: Some(6) }, value: 20.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 7, end: None }, value: 40.0 }).unwrap();
+
+        let above: Vec<&SerieEntry<u8, f32>, 4> = timeseries.buckets_above(&20.0).collect();
+        let below: Vec<&SerieEntry<u8, f32>, 4> = timeseries.buckets_below(&20.0).collect();
+
+        assert_eq!(above, [&timeseries.buckets[1], &timeseries.buckets[3]]);
+        assert_eq!(below, [&timeseries.buckets[0]]);
+        assert_eq!(timeseries.buckets_above(&40.0).count(), 0);
+    }
+
+    #[test]
+    fn tuple_deviates_on_second_element() {
+        assert!(!(20.0f32, 50.0f32).deviate(&(19.9, 49.9), &(0.3, 1.0)));
+        assert!((20.0f32, 50.0f32).deviate(&(19.9, 48.0), &(0.3, 1.0)));
+        assert!((20.0f32, 50.0f32, 1.0f64).deviate(&(19.9, 48.0, 1.0), &(0.3, 1.0, 0.1)));
+        assert!((20.0f32, 50.0f32, 1.0f64, 5.0f32).deviate(&(19.9, 48.0, 1.0, 5.0), &(0.3, 1.0, 0.1, 0.1)));
+        assert!(!(20.0f32, 50.0f32, 1.0f64, 5.0f32).deviate(&(19.9, 49.5, 1.0, 5.0), &(0.3, 1.0, 0.1, 0.1)));
+    }
+
+    #[test]
+    fn tuple_series_splits_on_second_channel() {
+        let mut timeseries: Series<10, u32, (f32, f32)> = Series::new((0.3, 1.0));
+
+        assert!(timeseries.append_monotonic( 1_u32, (20.0, 50.0)));
+        assert!(timeseries.append_monotonic( 2_u32, (19.9, 49.5)));
+        assert!(timeseries.append_monotonic( 3_u32, (20.0, 48.0)));
+
+        let mut expected: Vec<SerieEntry<u32, (f32, f32)>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: (20.0, 50.0) }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 3, end: None }, value: (20.0, 48.0) }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn pop_last_after_split() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 3_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 5_u8, 12.3f32));
+
+        assert_eq!(timeseries.pop_last(), Some(SerieEntry { range: Range { start: 5, end: None }, value: 12.3 }));
+        assert_eq!(timeseries.ends_at(), Some(&3));
+        assert_eq!(timeseries.pop_last(), Some(SerieEntry { range: Range { start: 1, end: Some(3) }, value: 32.6 }));
+        assert_eq!(timeseries.ends_at(), None);
+        assert_eq!(timeseries.pop_last(), None);
+    }
+
+    #[test]
+    fn check_invariants_valid() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert_eq!(timeseries.check_invariants(), Ok(()));
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 6_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 8_u8, 12.3f32));
+        assert!(timeseries.append_monotonic( 9_u8, 2.3f32));
+        assert!(timeseries.split_bucket(0, 4));
+        timeseries.close();
+
+        assert_eq!(timeseries.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn check_invariants_not_monotonic() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(4) }, value: 32.6 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 3, end: None }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.check_invariants(), Err(InvariantError::NotMonotonic { index: 1 }));
+    }
+
+    #[test]
+    fn check_invariants_repeated_single_point() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: None }, value: 32.6 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(3) }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.check_invariants(), Err(InvariantError::NotMonotonic { index: 1 }));
+    }
+
+    #[test]
+    fn check_invariants_backwards_range() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: None }, value: 32.6 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: Some(4) }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.check_invariants(), Err(InvariantError::BackwardsRange { index: 1 }));
+    }
+
+    #[test]
+    fn distance_is_absolute_difference() {
+        assert_eq!(32.5f32.distance(&32.0), 0.5);
+        assert_eq!(32.0f32.distance(&32.5), 0.5);
+        assert_eq!(1.0f64.distance(&-2.0), 3.0);
+    }
+
+    #[test]
+    fn signed_distance_saturates() {
+        assert_eq!(127i8.distance(&-128), i8::MAX);
+        assert_eq!(i8::MIN.distance(&i8::MAX), i8::MAX);
+        assert_eq!(i128::MIN.distance(&i128::MAX), i128::MAX);
+        assert_eq!((-3i8).distance(&4), 7);
+        assert_eq!(5i128.distance(&-5), 10);
+    }
+
+    #[test]
+    fn distance_is_not_the_float_merge_criterion_on_rises() {
+        assert_eq!(1.0f32.distance(&100.0), 99.0);
+        assert!(!1.0f32.deviate(&100.0, &1.0));
+        assert_eq!(1.0f64.distance(&100.0), 99.0);
+        assert!(!1.0f64.deviate(&100.0, &1.0));
+    }
+
+    #[test]
+    fn distance_agrees_with_deviate_on_drops() {
+        for (bucket, reading) in [(32.6f32, 32.5f32), (32.6, 32.2), (32.6, 12.3), (32.6, 32.6)] {
+            assert_eq!(bucket.deviate(&reading, &0.3), bucket.distance(&reading) > 0.3);
+        }
+    }
+
+    #[test]
+    fn max_span_splits_flat_signal() {
+        let mut timeseries: Series<10, u8, f32> = Series::new_with_max_span(0.3f32, 3);
+
+        for at in 0..10_u8 {
+            assert!(timeseries.append_monotonic( at, 32.6f32));
+        }
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 0, end: Some(3) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 4, end: Some(7) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 8, end: Some(9) }, value: 32.6 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn max_span_still_splits_on_deviation() {
+        let mut timeseries: Series<10, u8, f32> = Series::new_with_max_span(0.3f32, 100);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 50_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 60_u8, 12.3f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(50) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 60, end: None }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn iter_ranges_three_buckets() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 3_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 5_u8, 12.3f32));
+        assert!(timeseries.append_monotonic( 7_u8, 2.3f32));
+        assert!(timeseries.append_monotonic( 9_u8, 2.4f32));
+
+        let ranges: Vec<(u8, Option<u8>), 3> = timeseries.iter_ranges().map(|r| (*r.start(), r.end().copied())).collect();
+
+        assert_eq!(ranges, [(1, Some(3)), (5, None), (7, Some(9))]);
+    }
+
+    #[test]
+    fn nan_always_deviates() {
+        assert!(32.6f32.deviate(&f32::NAN, &0.3));
+        assert!(f32::NAN.deviate(&32.6, &0.3));
+        assert!(f64::NAN.deviate(&f64::NAN, &0.3));
+    }
+
+    #[test]
+    fn nan_reading_forms_own_bucket() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, f32::NAN));
+        assert!(timeseries.append_monotonic( 3_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_u8, 32.7f32));
+
+        assert_eq!(timeseries.buckets.len(), 3);
+        assert_eq!(timeseries.buckets[0].range, Range { start: 1, end: None });
+        assert_eq!(timeseries.buckets[1].range, Range { start: 2, end: None });
+        assert!(timeseries.buckets[1].value.is_nan());
+        assert_eq!(timeseries.buckets[2].range, Range { start: 3, end: Some(4) });
+    }
+
+    #[test]
+    fn time_in_bands_two_bands() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 0, end: Some(10) }, value: 5.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 12, end: Some(15) }, value: 25.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 16, end: Some(20) }, value: 10.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 22, end: None }, value: 30.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 24, end: Some(30) }, value: -1.0 }).unwrap();
+
+        let bands: Vec<(f32, u8), 2> = timeseries.time_in_bands(&[0.0, 20.0]);
+
+        assert_eq!(bands, [(0.0, 14), (20.0, 3)]);
+    }
+
+    #[test]
+    fn time_in_bands_empty_series() {
+        let timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        let bands: Vec<(f32, u8), 2> = timeseries.time_in_bands(&[0.0, 20.0]);
+
+        assert_eq!(bands, [(0.0, 0), (20.0, 0)]);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct NoDeviate(u8);
+
+    impl Deviate for NoDeviate {
+        fn deviate(&self, _other: &Self, _max: &Self) -> bool {
+            panic!("deviate called for equal values")
+        }
+
+        fn equal_may_deviate(_max: &Self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn append_equal_values_skips_deviate() {
+        let mut timeseries: Series<10, u8, NoDeviate> = Series::new(NoDeviate(0));
+
+        assert!(timeseries.append_monotonic( 1_u8, NoDeviate(7)));
+        assert!(timeseries.append_monotonic( 2_u8, NoDeviate(7)));
+        assert!(timeseries.append_monotonic( 3_u8, NoDeviate(7)));
+
+        let mut expected: Vec<SerieEntry<u8, NoDeviate>, 1> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(3) }, value: NoDeviate(7) }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected);
+    }
+
+    #[test]
+    fn append_equal_values_consults_deviate_for_none_threshold() {
+        let mut timeseries: Series<10, u8, Option<f32>> = Series::new(None);
+
+        assert!(timeseries.append_monotonic( 1_u8, Some(1.0f32)));
+        assert!(timeseries.append_monotonic( 2_u8, Some(1.0f32)));
+        assert!(timeseries.append_monotonic( 3_u8, None));
+        assert!(timeseries.append_monotonic( 4_u8, None));
+
+        assert_eq!(timeseries.len(), 4);
+    }
+
+    #[test]
+    fn append_equal_values_consults_deviate_for_negative_threshold() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(-1.0f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 5.0f32));
+        assert!(timeseries.append_monotonic( 2_u8, 5.0f32));
+
+        assert_eq!(timeseries.len(), 2);
+    }
+
+    #[test]
+    fn append_flat_signal_uses_one_bucket() {
+        let mut timeseries: Series<4, u16, f32> = Series::new(0.0f32);
+
+        for at in 0..1000_u16 {
+            assert!(timeseries.append_monotonic(at, 21.5f32));
+        }
+
+        assert_eq!(timeseries.len(), 1);
+        assert_eq!(timeseries.ends_at(), Some(&999));
+    }
+
+    #[test]
+    fn append_equal_value_still_respects_max_span() {
+        let mut timeseries: Series<10, u8, f32> = Series::new_with_max_span(0.3f32, 5);
+
+        assert!(timeseries.append_monotonic( 1_u8, 20.0f32));
+        assert!(timeseries.append_monotonic( 6_u8, 20.0f32));
+        assert!(timeseries.append_monotonic( 7_u8, 20.0f32));
+
+        assert_eq!(timeseries.len(), 2);
+    }
+
+    #[test]
+    fn append_equal_nan_values_still_split() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, f32::NAN));
+        assert!(timeseries.append_monotonic( 2_u8, f32::NAN));
+
+        assert_eq!(timeseries.len(), 2);
+    }
+
+    #[test]
+    fn into_buckets_round_trip() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 4_u8, 12.3f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 2> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 4, end: None }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.as_slice(), expected.as_slice());
+
+        let buckets = timeseries.into_buckets();
+        let mut rebuilt: Series<10, u8, f32> = Series::new(0.3f32);
+        rebuilt.buckets = buckets;
+
+        assert_eq!(rebuilt.as_slice(), expected.as_slice());
+        assert!(rebuilt.append_monotonic( 5_u8, 12.2f32));
+        assert_eq!(rebuilt.ends_at(), Some(&5));
+    }
+
+    #[test]
+    fn from_buckets_valid() {
+        let mut buckets: Vec<SerieEntry<u8, f32>, 10> = Vec::new();
+        buckets.push(SerieEntry { range: Range { start: 1, end: Some(4) }, value: 32.6 }).unwrap();
+        buckets.push(SerieEntry { range: Range { start: 6, end: None }, value: 12.3 }).unwrap();
+
+        let mut timeseries: Series<10, u8, f32> = Series::from_buckets(0.3f32, buckets).unwrap();
+
+        assert!(timeseries.append_monotonic( 7_u8, 12.2f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 2> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(4) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 6, end: Some(7) }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn from_buckets_empty() {
+        let timeseries: Series<10, u8, f32> = Series::from_buckets(0.3f32, Vec::new()).unwrap();
+
+        assert!(timeseries.is_empty());
+    }
+
+    #[test]
+    fn from_buckets_out_of_order() {
+        let mut buckets: Vec<SerieEntry<u8, f32>, 10> = Vec::new();
+        buckets.push(SerieEntry { range: Range { start: 6, end: None }, value: 32.6 }).unwrap();
+        buckets.push(SerieEntry { range: Range { start: 1, end: Some(4) }, value: 12.3 }).unwrap();
+
+        assert_eq!(Series::from_buckets(0.3f32, buckets), Err(InvariantError::NotMonotonic { index: 1 }));
+    }
+
+    #[test]
+    fn from_buckets_overlapping() {
+        let mut buckets: Vec<SerieEntry<u8, f32>, 10> = Vec::new();
+        buckets.push(SerieEntry { range: Range { start: 1, end: Some(4) }, value: 32.6 }).unwrap();
+        buckets.push(SerieEntry { range: Range { start: 3, end: Some(5) }, value: 12.3 }).unwrap();
+
+        assert_eq!(Series::from_buckets(0.3f32, buckets), Err(InvariantError::NotMonotonic { index: 1 }));
+    }
+
+    #[test]
+    fn from_buckets_backwards_range() {
+        let mut buckets: Vec<SerieEntry<u8, f32>, 10> = Vec::new();
+        buckets.push(SerieEntry { range: Range { start: 4, end: Some(1) }, value: 32.6 }).unwrap();
+
+        assert_eq!(Series::from_buckets(0.3f32, buckets), Err(InvariantError::BackwardsRange { index: 0 }));
+    }
+
+    #[test]
+    fn envelope_two_buckets() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 0, end: Some(14) }, value: 30.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 15, end: Some(25) }, value: 10.0 }).unwrap();
+
+        let envelope: Vec<(u8, f32, f32), 4> = timeseries.envelope(10);
+
+        assert_eq!(envelope, [(0, 30.0, 30.0), (10, 10.0, 30.0), (20, 10.0, 10.0)]);
+    }
+
+    #[test]
+    fn envelope_skips_empty_tiles() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 0, end: Some(4) }, value: 30.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 25, end: None }, value: 10.0 }).unwrap();
+
+        let envelope: Vec<(u8, f32, f32), 4> = timeseries.envelope(10);
+
+        assert_eq!(envelope, [(0, 30.0, 30.0), (20, 10.0, 10.0)]);
+    }
+
+    #[test]
+    fn envelope_stops_at_capacity() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 0, end: Some(14) }, value: 30.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 15, end: Some(25) }, value: 10.0 }).unwrap();
+
+        let envelope: Vec<(u8, f32, f32), 2> = timeseries.envelope(10);
+
+        assert_eq!(envelope, [(0, 30.0, 30.0), (10, 10.0, 30.0)]);
+    }
+
+    #[test]
+    fn envelope_empty_series() {
+        let timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        let envelope: Vec<(u8, f32, f32), 2> = timeseries.envelope(10);
+
+        assert!(envelope.is_empty());
+    }
+
+    #[test]
+    fn duration_values_compress() {
+        let mut timeseries: Series<10, u32, Duration> = Series::new(Duration::from_millis(5));
+
+        assert!(timeseries.append_monotonic( 1_u32, Duration::from_millis(100)));
+        assert!(timeseries.append_monotonic( 2_u32, Duration::from_millis(104)));
+        assert!(timeseries.append_monotonic( 3_u32, Duration::from_millis(97)));
+        assert!(timeseries.append_monotonic( 4_u32, Duration::from_millis(250)));
+
+        let mut expected: Vec<SerieEntry<u32, Duration>, 2> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(3) }, value: Duration::from_millis(100) }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 4, end: None }, value: Duration::from_millis(250) }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected);
+    }
+
+    #[test]
+    fn duration_deviate_is_underflow_safe() {
+        let max = Duration::from_millis(5);
+
+        assert!(Duration::from_millis(250).deviate(&Duration::from_millis(100), &max));
+        assert!(Duration::from_millis(100).deviate(&Duration::from_millis(250), &max));
+        assert!(!Duration::ZERO.deviate(&Duration::from_millis(5), &max));
+        assert_eq!(Duration::ZERO.distance(&Duration::from_millis(5)), max);
+    }
+
+    #[test]
+    fn compact_reclaims_fragmented_buckets() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 32.6 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 4, end: None }, value: 32.5 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: None }, value: 12.3 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 8, end: Some(9) }, value: 12.3 }).unwrap();
+
+        let span = (timeseries.starts_at().copied(), timeseries.ends_at().copied());
+
+        assert_eq!(timeseries.compact(), 2);
+        assert_eq!(timeseries.len(), 2);
+        assert_eq!((timeseries.starts_at().copied(), timeseries.ends_at().copied()), span);
+        assert_eq!(timeseries.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn compact_without_fragmentation() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 6_u8, 12.3f32));
+
+        assert_eq!(timeseries.compact(), 0);
+        assert_eq!(timeseries.len(), 2);
+    }
+
+    #[test]
+    fn run_lengths_of_compressed_points() {
+        let points = [(1_u8, 32.6f32), (2, 32.7), (3, 32.5), (4, 33.8), (6, 34.0), (8, 28.2), (10, 12.3)];
+        let (timeseries, consumed): (Series<10, u8, f32>, usize) = Series::compress_slice(0.3f32, &points);
+
+        assert_eq!(consumed, points.len());
+        assert_eq!(timeseries.run_lengths(&points), [5, 1, 1]);
+    }
+
+    #[test]
+    fn run_lengths_ignores_points_outside_buckets() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 2, end: Some(4) }, value: 32.6 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 8, end: None }, value: 12.3 }).unwrap();
+
+        let points = [(1_u8, 32.6f32), (3, 32.6), (5, 20.0), (8, 12.3), (9, 12.3)];
+
+        assert_eq!(timeseries.run_lengths(&points), [1, 1]);
+    }
+
+    #[test]
+    fn append_or_else_reports_overflow() {
+        let mut timeseries: Series<2, u8, f32> = Series::new(0.3f32);
+        let mut rejected: Option<(u8, f32)> = None;
+
+        assert!(timeseries.append_or_else( 1_u8, 32.6f32, |at, value| rejected = Some((at, value))));
+        assert!(timeseries.append_or_else( 2_u8, 12.3f32, |at, value| rejected = Some((at, value))));
+        assert_eq!(rejected, None);
+
+        assert!(!timeseries.append_or_else( 3_u8, 2.3f32, |at, value| rejected = Some((at, value))));
+        assert_eq!(rejected, Some((3, 2.3)));
+        assert_eq!(timeseries.len(), 2);
+    }
+
+    #[test]
+    fn append_or_else_merges_into_full_series() {
+        let mut timeseries: Series<1, u8, f32> = Series::new(0.3f32);
+        let mut calls = 0;
+
+        assert!(timeseries.append_or_else( 1_u8, 1.0f32, |_, _| calls += 1));
+        assert!(timeseries.append_or_else( 2_u8, 1.0f32, |_, _| calls += 1));
+        assert!(timeseries.append_or_else( 3_u8, 0.9f32, |_, _| calls += 1));
+
+        assert_eq!(calls, 0);
+        assert_eq!(timeseries.ends_at(), Some(&3));
+
+        assert!(!timeseries.append_or_else( 4_u8, 0.1f32, |_, _| calls += 1));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn append_or_else_ignores_non_monotonic() {
+        let mut timeseries: Series<1, u8, f32> = Series::new(0.3f32);
+        let mut calls = 0;
+
+        assert!(timeseries.append_or_else( 5_u8, 32.6f32, |_, _| calls += 1));
+        assert!(!timeseries.append_or_else( 5_u8, 12.3f32, |_, _| calls += 1));
+        assert!(!timeseries.append_or_else( 2_u8, 12.3f32, |_, _| calls += 1));
+
+        assert_eq!(calls, 0);
+    }
+
+    #[test]
+    fn buckets_eq_ignores_max_deviation() {
+        let mut a: Series<10, u8, f32> = Series::new(0.3f32);
+        let mut b: Series<10, u8, f32> = Series::new(0.5f32);
+
+        assert!(a.append_monotonic( 1_u8, 32.6f32));
+        assert!(a.append_monotonic( 4_u8, 12.3f32));
+        assert!(b.append_monotonic( 1_u8, 32.6f32));
+        assert!(b.append_monotonic( 4_u8, 12.3f32));
+
+        assert!(a != b);
+        assert!(a.buckets_eq(&b));
+    }
+
+    #[test]
+    fn buckets_eq_differing_buckets() {
+        let mut a: Series<10, u8, f32> = Series::new(0.3f32);
+        let mut b: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(a.append_monotonic( 1_u8, 32.6f32));
+        assert!(b.append_monotonic( 1_u8, 32.6f32));
+        assert!(b.append_monotonic( 4_u8, 12.3f32));
+
+        assert!(!a.buckets_eq(&b));
+    }
+
+    #[test]
+    fn reconstruct_into_fills_buffer() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 0, end: Some(3) }, value: 32.6 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: Some(8) }, value: 12.3 }).unwrap();
+
+        let mut out = [0.0f32; 4];
+
+        assert_eq!(timeseries.reconstruct_into(0, 2, &mut out), 4);
+        assert_eq!(out, [32.6, 32.6, 32.6, 12.3]);
+    }
+
+    #[test]
+    fn reconstruct_into_stops_past_span() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 0, end: Some(3) }, value: 32.6 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: Some(8) }, value: 12.3 }).unwrap();
+
+        let mut out = [0.0f32; 8];
+
+        assert_eq!(timeseries.reconstruct_into(1, 3, &mut out), 3);
+        assert_eq!(out[..3], [32.6, 32.6, 12.3]);
+        assert_eq!(timeseries.reconstruct_into(u8::MAX, 1, &mut out), 0);
+    }
+
+    #[test]
+    fn reconstruct_into_before_first_bucket() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+        let mut out = [0.0f32; 4];
+
+        assert_eq!(timeseries.reconstruct_into(0, 1, &mut out), 0);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 2, end: Some(3) }, value: 32.6 }).unwrap();
+
+        assert_eq!(timeseries.reconstruct_into(0, 1, &mut out), 0);
+    }
+
+    #[test]
+    fn reconstruct_into_rejects_non_positive_step() {
+        let mut timeseries: Series<10, i8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 0, end: Some(3) }, value: 32.6 }).unwrap();
+
+        let mut out = [0.0f32; 4];
+
+        assert_eq!(timeseries.reconstruct_into(0, 0, &mut out), 0);
+        assert_eq!(timeseries.reconstruct_into(3, -1, &mut out), 0);
+        assert_eq!(out, [0.0; 4]);
+    }
+
+    #[test]
+    fn hysteresis_absorbs_dithering() {
+        let readings = [(1_u8, 10.0f32), (2, 11.2), (3, 10.0), (4, 11.2), (5, 10.1), (6, 11.2)];
+        // Hysteresis is symmetric, so it is compared against the symmetric plain mode: the
+        // default float mode only splits on drops and would not split this signal at all.
+        let mut plain: Series<10, u8, f32> = Series::new_asym(1.0f32, 1.0f32);
+        let mut timeseries: Series<10, u8, f32> = Series::new_hysteresis(1.0f32, 0.5f32);
+
+        for (at, value) in readings {
+            assert!(plain.append_monotonic(at, value));
+            assert!(timeseries.append_monotonic(at, value));
+        }
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 2> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(5) }, value: 10.0 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 6, end: None }, value: 11.2 }).unwrap();
+
+        assert_eq!(plain.len(), 6);
+        assert_eq!(timeseries.buckets, expected);
+    }
+
+    #[test]
+    fn hysteresis_keeps_sustained_step() {
+        let mut timeseries: Series<10, u8, f64> = Series::new_hysteresis(1.0f64, 0.5f64);
+
+        assert!(timeseries.append_monotonic( 1_u8, 10.0f64));
+        assert!(timeseries.append_monotonic( 2_u8, 11.5f64));
+        assert!(timeseries.append_monotonic( 3_u8, 11.6f64));
+        assert!(timeseries.append_monotonic( 4_u8, 10.0f64));
+
+        let mut expected: Vec<SerieEntry<u8, f64>, 3> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1, end: None }, value: 10.0 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 2, end: Some(3) }, value: 11.5 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 4, end: None }, value: 10.0 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected);
+    }
+
+    #[test]
+    fn hysteresis_needs_close_threshold_to_rejoin() {
+        let mut timeseries: Series<10, u8, f32> = Series::new_hysteresis(1.0f32, 0.5f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 10.0f32));
+        assert!(timeseries.append_monotonic( 2_u8, 11.2f32));
+        assert!(timeseries.append_monotonic( 3_u8, 9.4f32));
+
+        assert_eq!(timeseries.len(), 3);
+    }
+
+    #[test]
+    fn auto_coarsen_frees_capacity() {
+        let mut timeseries: Series<3, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 32.0f32));
+        assert!(timeseries.append_monotonic( 3_u8, 31.5f32));
+        assert!(!timeseries.append_monotonic( 4_u8, 20.0f32));
+
+        timeseries.auto_coarsen(4.0);
+
+        assert_eq!(timeseries.max_deviation, 1.2);
+        assert_eq!(timeseries.len(), 1);
+        assert!(timeseries.append_monotonic( 4_u8, 20.0f32));
+        assert_eq!(timeseries.len(), 2);
+    }
+
+    #[test]
+    fn auto_coarsen_ignores_series_with_room() {
+        let mut timeseries: Series<3, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 32.0f32));
+
+        timeseries.auto_coarsen(4.0);
+
+        assert_eq!(timeseries.max_deviation, 0.3);
+        assert_eq!(timeseries.len(), 2);
+    }
+
+    #[test]
+    fn estimated_samples_from_ranges() {
+        let mut timeseries: Series<10, u32, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 0, end: Some(50) }, value: 32.6 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 60, end: None }, value: 12.3 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 70, end: Some(95) }, value: 2.3 }).unwrap();
+
+        assert_eq!(timeseries.estimated_samples(10), 6 + 1 + 3);
+    }
+
+    #[test]
+    fn estimated_samples_zero_period() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+
+        assert_eq!(timeseries.estimated_samples(1), 1);
+        assert_eq!(timeseries.estimated_samples(0), 0);
+    }
+
+    #[test]
+    fn from_merged_interleaves_sources() {
+        let a = [(1_u8, 32.6f32), (4, 32.5), (6, 12.3)];
+        let b = [(2_u8, 32.7f32), (4, 20.0), (5, 32.4), (7, 12.2)];
+        let mut sources = [a.iter().copied().peekable(), b.iter().copied().peekable()];
+
+        let timeseries: Series<10, u8, f32> = Series::from_merged(0.3f32, &mut sources);
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 2> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(5) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 6, end: Some(7) }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected);
+    }
+
+    #[test]
+    fn from_merged_stops_when_full() {
+        let a = [(1_u8, 30.0f32), (3, 10.0), (5, -10.0)];
+        let b = [(2_u8, 20.0f32), (4, 0.0)];
+        let mut sources = [a.iter().copied().peekable(), b.iter().copied().peekable()];
+
+        let timeseries: Series<3, u8, f32> = Series::from_merged(0.3f32, &mut sources);
+
+        assert_eq!(timeseries.ends_at(), Some(&3));
+        assert_eq!(sources[1].next(), Some((4, 0.0)));
+    }
+
+    #[test]
+    fn buckets_in_inclusive_range() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(4) }, value: 32.6 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 10, end: Some(12) }, value: 20.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 18, end: None }, value: 12.3 }).unwrap();
+
+        let inside: Vec<u8, 3> = timeseries.buckets_in(9..=13).map(|b| *b.start()).collect();
+        let partial: Vec<u8, 3> = timeseries.buckets_in(4..=10).map(|b| *b.start()).collect();
+        let none: Vec<u8, 3> = timeseries.buckets_in(13..=17).map(|b| *b.start()).collect();
+
+        assert_eq!(inside, [10]);
+        assert_eq!(partial, [1, 10]);
+        assert!(none.is_empty());
+        assert_eq!(timeseries.buckets_in(0..=u8::MAX).count(), 3);
+    }
+
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Stamp(u8);
+
+    #[test]
+    fn point_refs_without_clone() {
+        let mut timeseries: Series<10, Stamp, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic(Stamp(1), 32.6f32));
+        assert!(timeseries.append_monotonic(Stamp(4), 32.7f32));
+        assert!(timeseries.append_monotonic(Stamp(6), 12.3f32));
+
+        let points: Vec<(u8, f32), 4> = timeseries.point_refs().map(|(at, value)| (at.0, *value)).collect();
+
+        assert_eq!(points, [(1, 32.6), (4, 32.6), (6, 12.3)]);
+        assert!(timeseries.point_refs().zip(timeseries.point_refs().skip(1)).all(|(a, b)| a.0 < b.0));
+    }
+
+    #[test]
+    fn time_of_extremes() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert_eq!(timeseries.time_of_max(), None);
+        assert_eq!(timeseries.time_of_min(), None);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(3) }, value: 20.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 4, end: None }, value: 40.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: Some(7) }, value: 25.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 9, end: None }, value: 5.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 11, end: None }, value: 40.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 13, end: None }, value: 5.0 }).unwrap();
+
+        assert_eq!(timeseries.time_of_max(), Some(&4));
+        assert_eq!(timeseries.time_of_min(), Some(&9));
+    }
+
+    #[test]
+    fn merge_recompress_joins_seam() {
+        let mut a: Series<10, u8, f32> = Series::new(0.3f32);
+        let mut b: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(a.append_monotonic( 1_u8, 40.0f32));
+        assert!(a.append_monotonic( 2_u8, 32.6f32));
+        assert!(a.append_monotonic( 4_u8, 32.6f32));
+        assert!(b.append_monotonic( 6_u8, 32.5f32));
+        assert!(b.append_monotonic( 8_u8, 32.5f32));
+        assert!(b.append_monotonic( 9_u8, 2.3f32));
+
+        assert_eq!(a.merge_recompress(&b), Ok(()));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1, end: None }, value: 40.0 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 2, end: Some(8) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 9, end: None }, value: 2.3 }).unwrap();
+
+        assert_eq!(a.buckets, expected);
+    }
+
+    #[test]
+    fn merge_recompress_rejects_overlap() {
+        let mut a: Series<10, u8, f32> = Series::new(0.3f32);
+        let mut b: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(a.append_monotonic( 1_u8, 32.6f32));
+        assert!(a.append_monotonic( 5_u8, 32.6f32));
+        assert!(b.append_monotonic( 6_u8, 12.3f32));
+        assert!(b.append_monotonic( 7_u8, 2.3f32));
+        assert!(b.append_monotonic( 8_u8, 2.3f32));
+
+        assert_eq!(b.merge_recompress(&a), Err(0));
+        assert_eq!(a.merge_recompress(&b), Ok(()));
+        assert_eq!(a.ends_at(), Some(&8));
+    }
+
+    #[test]
+    fn integer_values_deviate_symmetrically() {
+        assert!(10_u8.deviate(&13, &2));
+        assert!(13_u8.deviate(&10, &2));
+        assert!(!10_u8.deviate(&12, &2));
+        assert!(i8::MIN.deviate(&i8::MAX, &100));
+        assert!(!(-5_i32).deviate(&-3, &2));
+    }
+
+    #[test]
+    fn checked_integral_and_mean() {
+        let mut timeseries: Series<10, u8, i32> = Series::new(2);
+
+        assert_eq!(timeseries.checked_integral(), Some(0));
+        assert_eq!(timeseries.checked_mean(), None);
+
+        assert!(timeseries.append_monotonic( 0_u8, 10));
+        assert!(timeseries.append_monotonic( 4_u8, 11));
+        assert!(timeseries.append_monotonic( 5_u8, 40));
+        assert!(timeseries.append_monotonic( 9_u8, 40));
+        assert!(timeseries.append_monotonic( 12_u8, 0));
+
+        assert_eq!(timeseries.checked_integral(), Some(10 * 4 + 40 * 4));
+        assert_eq!(timeseries.checked_mean(), Some(25));
+    }
+
+    #[test]
+    fn checked_integral_overflow() {
+        let mut timeseries: Series<10, u8, i16> = Series::new(2);
+
+        assert!(timeseries.append_monotonic( 0_u8, 20_000));
+        assert!(timeseries.append_monotonic( 2_u8, 20_000));
+
+        assert_eq!(timeseries.checked_integral(), None);
+        assert_eq!(timeseries.checked_mean(), None);
+    }
+
+    #[test]
+    fn checked_integral_128_bit() {
+        let mut wide: Series<10, u64, i128> = Series::new(2);
+
+        assert!(wide.append_monotonic( 0_u64, i128::from(i64::MAX)));
+        assert!(wide.append_monotonic( 4_u64, i128::from(i64::MAX)));
+        assert!(wide.append_monotonic( 5_u64, -1));
+        assert!(wide.append_monotonic( 7_u64, -1));
+
+        assert_eq!(wide.checked_integral(), Some(i128::from(i64::MAX) * 4 - 2));
+        assert_eq!(wide.checked_mean(), Some((i128::from(i64::MAX) * 4 - 2) / 6));
+
+        let mut unsigned: Series<10, u64, u128> = Series::new(2);
+
+        assert!(unsigned.append_monotonic( 0_u64, u128::MAX / 2));
+        assert!(unsigned.append_monotonic( 3_u64, u128::MAX / 2));
+
+        assert_eq!(unsigned.checked_integral(), None);
+    }
+
+    #[test]
+    fn checked_integral_duration_does_not_fit() {
+        let mut timeseries: Series<10, u16, i8> = Series::new(2);
+
+        assert!(timeseries.append_monotonic( 0_u16, 1));
+        assert!(timeseries.append_monotonic( 300_u16, 1));
+
+        assert_eq!(timeseries.checked_integral(), None);
+    }
+
+    #[test]
+    fn retain_recent_keeps_window() {
+        let mut timeseries: Series<12, u32, f32> = Series::new(0.3f32);
+
+        for (i, at) in (0..100_u32).step_by(10).enumerate() {
+            assert!(timeseries.append_monotonic(at, 100.0 - i as f32));
+            assert!(timeseries.append_monotonic(at + 5, 100.0 - i as f32));
+        }
+
+        timeseries.retain_recent(22);
+
+        let starts: Vec<u32, 10> = timeseries.iter_ranges().map(|r| *r.start()).collect();
+
+        assert_eq!(starts, [70, 80, 90]);
+        assert_eq!(timeseries.ends_at(), Some(&95));
+    }
+
+    #[test]
+    fn retain_recent_span_covers_everything() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.retain_recent(5);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_u8, 12.3f32));
+
+        timeseries.retain_recent(200);
+
+        assert_eq!(timeseries.len(), 2);
+    }
+
+    #[test]
+    fn footprint_matches_size_of() {
+        const FOOTPRINT: usize = Series::<10, u32, f32>::footprint();
+
+        assert_eq!(FOOTPRINT, core::mem::size_of::<Series<10, u32, f32>>());
+        assert_eq!(Series::<10, u32, f32>::bucket_size(), core::mem::size_of::<SerieEntry<u32, f32>>());
+        assert!(Series::<20, u32, f32>::footprint() >= FOOTPRINT + 10 * Series::<10, u32, f32>::bucket_size());
+    }
+
+    #[test]
+    fn extend_last_to_open_bucket() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(!timeseries.extend_last_to(5));
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 3_u8, 12.3f32));
+        assert!(timeseries.extend_last_to(9));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 2> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1, end: None }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 3, end: Some(9) }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected);
+    }
+
+    #[test]
+    fn extend_last_to_ranged_bucket() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 3_u8, 32.5f32));
+        assert!(timeseries.extend_last_to(9));
+
+        assert_eq!(timeseries.len(), 1);
+        assert_eq!(timeseries.ends_at(), Some(&9));
+    }
+
+    #[test]
+    fn extend_last_to_rejects_backwards() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 3_u8, 32.5f32));
+
+        assert!(!timeseries.extend_last_to(3));
+        assert!(!timeseries.extend_last_to(2));
+        assert_eq!(timeseries.ends_at(), Some(&3));
+    }
+
+    #[test]
+    fn metric_l1_splits_on_combined_drift() {
+        let mut l1: Series<10, u8, [f32; 2]> = Series::new_metric([1.0, 1.0], Metric::L1);
+        let mut inf: Series<10, u8, [f32; 2]> = Series::new_metric([1.0, 1.0], Metric::Inf);
+
+        for series in [&mut l1, &mut inf] {
+            assert!(series.append_monotonic( 1_u8, [10.0, 20.0]));
+            assert!(series.append_monotonic( 2_u8, [10.6, 19.4]));
+        }
+
+        assert_eq!(l1.len(), 2);
+        assert_eq!(inf.len(), 1);
+    }
+
+    #[test]
+    fn metric_nan_channel_always_deviates() {
+        let mut l1: Series<10, u8, [f32; 2]> = Series::new_metric([1.0, 1.0], Metric::L1);
+        let mut inf: Series<10, u8, [f32; 2]> = Series::new_metric([1.0, 1.0], Metric::Inf);
+
+        for series in [&mut l1, &mut inf] {
+            assert!(series.append_monotonic( 1_u8, [10.0, 20.0]));
+            assert!(series.append_monotonic( 2_u8, [10.0, f32::NAN]));
+            assert!(series.append_monotonic( 3_u8, [10.0, 20.0]));
+        }
+
+        assert_eq!(l1.len(), 3);
+        assert_eq!(inf.len(), 3);
+    }
+
+    #[test]
+    fn metric_f64_channels() {
+        let mut l1: Series<10, u8, [f64; 2]> = Series::new_metric([1.0, 1.0], Metric::L1);
+
+        assert!(l1.append_monotonic( 1_u8, [10.0, 20.0]));
+        assert!(l1.append_monotonic( 2_u8, [10.6, 19.4]));
+        assert!(l1.append_monotonic( 3_u8, [f64::NAN, 19.4]));
+
+        assert_eq!(l1.len(), 3);
+    }
+
+    #[test]
+    fn metric_inf_and_l1_agree_on_single_channel_jump() {
+        let mut l1: Series<10, u8, [f32; 2]> = Series::new_metric([1.0, 1.0], Metric::L1);
+        let mut inf: Series<10, u8, [f32; 2]> = Series::new_metric([1.0, 1.0], Metric::Inf);
+
+        for series in [&mut l1, &mut inf] {
+            assert!(series.append_monotonic( 1_u8, [10.0, 20.0]));
+            assert!(series.append_monotonic( 2_u8, [10.2, 20.0]));
+            assert!(series.append_monotonic( 3_u8, [10.0, 23.0]));
+        }
+
+        assert_eq!(l1.len(), 2);
+        assert_eq!(inf.len(), 2);
+        assert!(l1.buckets_eq(&inf));
+    }
+
+    #[test]
+    fn metric_zero_threshold_channel() {
+        assert!(![1.0f32, 5.0].deviate_l1(&[1.2, 5.0], &[0.5, 0.0]));
+        assert!([1.0f32, 5.0].deviate_l1(&[1.0, 5.1], &[0.5, 0.0]));
+        assert!([1.0f32, 5.0].deviate_inf(&[1.0, 5.1], &[0.5, 0.0]));
+    }
+
+    #[test]
+    fn append_indexed_across_merge_and_split() {
+        let mut timeseries: Series<3, u8, f32> = Series::new(0.3f32);
+
+        assert_eq!(timeseries.append_indexed( 1_u8, 32.6f32), Some(0));
+        assert_eq!(timeseries.append_indexed( 2_u8, 32.5f32), Some(0));
+        assert_eq!(timeseries.append_indexed( 3_u8, 12.3f32), Some(1));
+        assert_eq!(timeseries.append_indexed( 4_u8, 12.2f32), Some(1));
+        assert_eq!(timeseries.append_indexed( 4_u8, 2.2f32), None);
+        assert_eq!(timeseries.append_indexed( 5_u8, 2.2f32), Some(2));
+        assert_eq!(timeseries.append_indexed( 6_u8, -20.0f32), None);
+    }
+
+    #[test]
+    fn repair_scrambled_buckets() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 10, end: None }, value: 2.3 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(4) }, value: 32.6 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 3, end: Some(5) }, value: 20.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: Some(8) }, value: 12.3 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 9, end: Some(7) }, value: 5.0 }).unwrap();
+
+        assert_eq!(timeseries.repair(), 2);
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(4) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 6, end: Some(8) }, value: 12.3 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 10, end: None }, value: 2.3 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected);
+        assert_eq!(timeseries.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn repair_valid_series_is_untouched() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_u8, 12.3f32));
+
+        assert_eq!(timeseries.repair(), 0);
+        assert_eq!(timeseries.len(), 2);
+    }
+
+    #[test]
+    fn weighted_median_differs_from_simple_median() {
+        let mut timeseries: Series<10, u8, i32> = Series::new(1);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 0, end: Some(1) }, value: 10 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 2, end: Some(3) }, value: 20 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 4, end: Some(5) }, value: 30 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: Some(46) }, value: 50 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 50, end: None }, value: 40 }).unwrap();
+
+        assert_eq!(timeseries.weighted_median(1), Some(50));
+        assert_eq!(timeseries.weighted_median(100), Some(40));
+    }
+
+    #[test]
+    fn weighted_median_total_weight_overflows() {
+        let mut timeseries: Series<10, u8, i32> = Series::new(1);
+
+        assert!(timeseries.append_monotonic( 1_u8, 10));
+        assert!(timeseries.append_monotonic( 5_u8, 20));
+
+        assert_eq!(timeseries.weighted_median(200), None);
+        assert_eq!(timeseries.weighted_median(100), Some(10));
+    }
+
+    #[test]
+    fn weighted_median_empty_series() {
+        let timeseries: Series<10, u8, i32> = Series::new(1);
+
+        assert_eq!(timeseries.weighted_median(1), None);
+    }
+
+    #[test]
+    fn bool_values_run_length_encode() {
+        let mut timeseries: Series<10, u32, bool> = Series::new(false);
+
+        assert!(timeseries.append_monotonic( 1_u32, true));
+        assert!(timeseries.append_monotonic( 2_u32, true));
+        assert!(timeseries.append_monotonic( 3_u32, false));
+        assert!(timeseries.append_monotonic( 4_u32, false));
+        assert!(timeseries.append_monotonic( 5_u32, true));
+
+        let mut expected: Vec<SerieEntry<u32, bool>, 3> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: true }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 3, end: Some(4) }, value: false }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 5, end: None }, value: true }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected);
+    }
+
+    #[test]
+    fn map_timestamps_scales_ranges() {
+        let mut timeseries: Series<10, u32, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u32, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_u32, 32.7f32));
+        assert!(timeseries.append_monotonic( 6_u32, 12.3f32));
+
+        let mut mapped: Series<10, u64, f32> = timeseries.map_timestamps(|ms| u64::from(*ms) * 1_000_000);
+
+        let mut expected: Vec<SerieEntry<u64, f32>, 2> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1_000_000, end: Some(4_000_000) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 6_000_000, end: None }, value: 12.3 }).unwrap();
+
+        assert_eq!(mapped.buckets, expected);
+        assert!(mapped.append_monotonic( 7_000_000_u64, 12.2f32));
+        assert_eq!(mapped.len(), 2);
+    }
+
+    #[test]
+    fn map_timestamps_keeps_non_strict() {
+        let mut timeseries: Series<10, u32, f32> = Series::new_non_strict(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u32, 32.6f32));
+
+        let mut mapped: Series<10, u64, f32> = timeseries.map_timestamps(|ms| u64::from(*ms) * 1_000);
+
+        assert!(mapped.append_monotonic( 1_000_u64, 12.3f32));
+        assert_eq!(mapped.len(), 1);
+        assert_eq!(mapped.buckets[0].value, 12.3);
+    }
+
+    #[test]
+    fn boundaries_deduplicates_touching_buckets() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 6_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 8_u8, 12.3f32));
+        assert!(timeseries.append_monotonic( 9_u8, 2.3f32));
+        assert!(timeseries.split_bucket(0, 4));
+
+        let boundaries: Vec<u8, 8> = timeseries.boundaries().collect();
+
+        assert_eq!(boundaries, [1, 4, 6, 8, 9]);
+    }
+
+    #[test]
+    fn append_with_looser_deviation_merges() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_with_deviation( 2_u8, 31.6f32, &2.0));
+        assert_eq!(timeseries.len(), 1);
+        assert_eq!(timeseries.max_deviation, 0.3);
+
+        assert!(timeseries.append_monotonic( 3_u8, 31.6f32));
+        assert_eq!(timeseries.len(), 2);
+    }
+
+    #[test]
+    fn append_with_tighter_deviation_splits() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_with_deviation( 2_u8, 32.5f32, &0.05));
+
+        assert_eq!(timeseries.len(), 2);
+    }
+
+    #[test]
+    fn merged_runs_groups_equal_values() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 5.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 4, end: None }, value: 5.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: None }, value: 7.0 }).unwrap();
+
+        let runs: Vec<(Range<u8>, &f32), 3> = timeseries.merged_runs().collect();
+
+        assert_eq!(runs, [(Range { start: 1, end: Some(4) }, &5.0), (Range { start: 6, end: None }, &7.0)]);
+        assert_eq!(timeseries.len(), 3);
+    }
+
+    #[test]
+    fn into_points_yields_endpoints() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 6_u8, 12.3f32));
+
+        let points: Vec<(u8, f32), 4> = timeseries.into_points().collect();
+
+        assert_eq!(points, [(1, 32.6), (4, 32.6), (6, 12.3)]);
+    }
+
+    #[test]
+    fn value_at_with_fills_gap() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 10.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: None }, value: 30.0 }).unwrap();
+
+        assert_eq!(timeseries.value_at_with(&3, GapFill::None), None);
+        assert_eq!(timeseries.value_at_with(&3, GapFill::HoldLast), Some(10.0));
+        assert_eq!(timeseries.value_at_with(&3, GapFill::HoldNext), Some(30.0));
+        assert_eq!(timeseries.value_at_with(&3, GapFill::Linear), Some(15.0));
+        assert_eq!(timeseries.value_at_with(&2, GapFill::None), Some(10.0));
+    }
+
+    #[test]
+    fn value_at_with_outside_series() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 10.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: None }, value: 30.0 }).unwrap();
+
+        assert_eq!(timeseries.value_at_with(&0, GapFill::HoldLast), None);
+        assert_eq!(timeseries.value_at_with(&0, GapFill::HoldNext), Some(10.0));
+        assert_eq!(timeseries.value_at_with(&0, GapFill::Linear), None);
+        assert_eq!(timeseries.value_at_with(&9, GapFill::HoldLast), Some(30.0));
+        assert_eq!(timeseries.value_at_with(&9, GapFill::HoldNext), None);
+        assert_eq!(timeseries.value_at_with(&9, GapFill::Linear), None);
+    }
+
+    #[test]
+    fn value_at_with_needs_no_arithmetic() {
+        let mut timeseries: Series<10, u32, bool> = Series::new(false);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: true }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 6, end: None }, value: false }).unwrap();
+
+        assert_eq!(timeseries.value_at_with(&3, GapFill::HoldLast), Some(true));
+        assert_eq!(timeseries.value_at_with(&3, GapFill::HoldNext), Some(false));
+        assert_eq!(timeseries.value_at_with(&3, GapFill::Linear), None);
+    }
+
+    #[test]
+    fn wide_integer_values_deviate_without_overflow() {
+        assert!(i128::MIN.deviate(&i128::MAX, &1));
+        assert!(i128::MAX.deviate(&i128::MIN, &i128::MAX));
+        assert!(!i128::MIN.deviate(&(i128::MIN + 5), &5));
+        assert!((i128::MIN + 6).deviate(&i128::MIN, &5));
+        assert!(!u128::MAX.deviate(&(u128::MAX - 5), &5));
+        assert!(u128::MAX.deviate(&0, &(u128::MAX - 1)));
+
+        let mut timeseries: Series<10, u8, i128> = Series::new(5);
+
+        assert!(timeseries.append_monotonic( 1_u8, i128::MAX));
+        assert!(timeseries.append_monotonic( 2_u8, i128::MAX - 5));
+        assert!(timeseries.append_monotonic( 3_u8, i128::MIN));
+
+        assert_eq!(timeseries.len(), 2);
+    }
+
+    fn wrapping_cmp(a: &u16, b: &u16) -> Ordering {
+        (a.wrapping_sub(*b) as i16).cmp(&0)
+    }
+
+    #[test]
+    fn new_with_cmp_accepts_wrapped_ticks() {
+        let mut timeseries: Series<10, u16, f32> = Series::new_with_cmp(0.3f32, wrapping_cmp);
+
+        assert!(timeseries.append_monotonic( 65000_u16, 32.6f32));
+        assert!(timeseries.append_monotonic( 65500_u16, 32.7f32));
+        assert!(timeseries.append_monotonic( 0_u16, 32.5f32));
+        assert!(timeseries.append_monotonic( 100_u16, 12.3f32));
+        assert!(!timeseries.append_monotonic( 65535_u16, 2.3f32));
+
+        let mut expected: Vec<SerieEntry<u16, f32>, 2> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 65000, end: Some(0) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 100, end: None }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected);
+    }
+
+    #[test]
+    fn new_with_cmp_queries_across_wrap() {
+        let mut timeseries: Series<10, u16, f32> = Series::new_with_cmp(0.3f32, wrapping_cmp);
+
+        assert!(timeseries.append_monotonic( 65000_u16, 10.0f32));
+        assert!(timeseries.append_monotonic( 65500_u16, 10.0f32));
+        assert!(timeseries.append_monotonic( 10_u16, 1.0f32));
+        assert!(timeseries.append_monotonic( 30_u16, 1.0f32));
+
+        assert_eq!(timeseries.check_invariants(), Ok(()));
+        assert_eq!(timeseries.starts_at(), Some(&65000));
+        assert_eq!(timeseries.ends_at(), Some(&30));
+        assert_eq!(timeseries.value_at(&65200), Some(&10.0));
+        assert_eq!(timeseries.value_at(&20), Some(&1.0));
+        assert_eq!(timeseries.value_at(&0), None);
+        assert_eq!(timeseries.value_at(&40), None);
+        assert_eq!(timeseries.nearest_value(&0), Some(&10.0));
+        assert_eq!(timeseries.count_in(&65400, &20), 2);
+
+        let mut other: Series<10, u16, f32> = Series::new_with_cmp(0.3f32, wrapping_cmp);
+        assert!(other.append_monotonic( 20_u16, 5.0f32));
+
+        assert_eq!(timeseries.merge(other).map_err(|(error, _)| error), Err(MergeError::Overlap));
+    }
+
+    #[test]
+    fn new_keeps_ord_for_ticks() {
+        let mut timeseries: Series<10, u16, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 65000_u16, 32.6f32));
+        assert!(!timeseries.append_monotonic( 0_u16, 32.5f32));
+    }
+
+    #[test]
+    fn reset_deviation_returns_previous() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert_eq!(timeseries.reset_deviation(0.1), 0.3);
+        assert_eq!(timeseries.max_deviation, 0.1);
+    }
+
+    #[test]
+    fn violates_tightened_deviation() {
+        let mut timeseries: Series<10, u8, f32> = Series::new(2.0f32);
+
+        assert!(!timeseries.violates_deviation(&0.1));
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 31.0f32));
+        assert!(timeseries.append_monotonic( 3_u8, 28.0f32));
+
+        assert!(!timeseries.violates_deviation(&5.0));
+        assert!(timeseries.violates_deviation(&1.0));
+    }
+
+    #[test]
+    fn add_concatenates_series() {
+        let mut a: Series<10, u8, f32> = Series::new(0.3f32);
+        let mut b: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(a.append_monotonic( 1_u8, 32.6f32));
+        assert!(a.append_monotonic( 3_u8, 32.5f32));
+        assert!(b.append_monotonic( 5_u8, 12.3f32));
+
+        let combined = a + b;
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 2> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(3) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 5, end: None }, value: 12.3 }).unwrap();
+
+        assert_eq!(combined.buckets, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot concatenate series: series overlap")]
+    fn add_panics_on_overlap() {
+        let mut a: Series<10, u8, f32> = Series::new(0.3f32);
+        let mut b: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(a.append_monotonic( 1_u8, 32.6f32));
+        assert!(a.append_monotonic( 5_u8, 32.5f32));
+        assert!(b.append_monotonic( 5_u8, 12.3f32));
+
+        let _ = a + b;
+    }
+
+    #[test]
+    fn merge_rejects_without_moving() {
+        let mut a: Series<2, u8, f32> = Series::new(0.3f32);
+        let mut b: Series<2, u8, f32> = Series::new(0.3f32);
+
+        assert!(a.append_monotonic( 1_u8, 32.6f32));
+        assert!(a.append_monotonic( 2_u8, 12.3f32));
+        assert!(b.append_monotonic( 5_u8, 2.3f32));
+
+        let Err((error, b)) = a.merge(b) else { panic!("merge should not fit") };
+
+        assert_eq!(error, MergeError::Capacity);
+        assert_eq!(a.len(), 2);
+        assert_eq!(b.starts_at(), Some(&5));
+    }
+
+    #[test]
+    fn would_merge_predicts_append() {
+        let mut timeseries: Series<2, u32, f32> = Series::new(0.3f32);
+
+        assert_eq!(timeseries.would_merge(&1, &32.6), MergeOutcome::NewBucket);
+        assert!(timeseries.append_monotonic( 1_u32, 32.6f32));
+
+        assert_eq!(timeseries.would_merge(&2, &32.5), MergeOutcome::Merge);
+        assert_eq!(timeseries.would_merge(&2, &12.3), MergeOutcome::NewBucket);
+        assert_eq!(timeseries.would_merge(&1, &32.6), MergeOutcome::Reject);
+        assert_eq!(timeseries.len(), 1);
+        assert_eq!(timeseries.ends_at(), Some(&1));
+
+        assert!(timeseries.append_monotonic( 2_u32, 12.3f32));
+
+        assert_eq!(timeseries.would_merge(&3, &12.3), MergeOutcome::Reject);
+        assert!(!timeseries.append_monotonic( 3_u32, 12.3f32));
+    }
+
+    #[test]
+    fn would_merge_on_evicting_series() {
+        let mut timeseries: Series<1, u32, f32> = Series::configured(0.3f32, Config { evicting: true, ..Config::default() });
+
+        assert!(timeseries.append_monotonic( 1_u32, 32.6f32));
+
+        assert_eq!(timeseries.would_merge(&2, &32.6), MergeOutcome::Merge);
+        assert_eq!(timeseries.would_merge(&2, &12.3), MergeOutcome::NewBucket);
+    }
+
+    #[test]
+    fn clamp_within_band() {
+        assert_eq!(33.0f32.clamp_within(&32.5, &0.25), 32.75);
+        assert_eq!(32.0f32.clamp_within(&32.5, &0.25), 32.25);
+        assert_eq!(32.6f32.clamp_within(&32.5, &0.25), 32.6);
+        assert_eq!((-3.0f64).clamp_within(&1.0, &2.0), -1.0);
+        assert!(f32::NAN.clamp_within(&32.5, &0.25).is_nan());
+    }
+
+    #[test]
+    fn clamp_within_saturates() {
+        assert_eq!(0u8.clamp_within(&2, &5), 0);
+        assert_eq!(255u8.clamp_within(&252, &5), 255);
+        assert_eq!(i8::MIN.clamp_within(&-126, &-5), i8::MIN);
+        assert_eq!(100i8.clamp_within(&-126, &5), -121);
+        assert_eq!(Duration::from_secs(9).clamp_within(&Duration::from_secs(1), &Duration::from_secs(3)), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn merge_all_fuses_in_time_order() {
+        let mut cores: [Series<10, u32, i32>; 3] = [Series::new(0), Series::new(0), Series::new(0)];
+
+        assert!(cores[2].append_monotonic( 7_u32, 1));
+        assert!(cores[2].append_monotonic( 9_u32, 1));
+        assert!(cores[0].append_monotonic( 1_u32, 10));
+        assert!(cores[0].append_monotonic( 2_u32, 10));
+        assert!(cores[1].append_monotonic( 4_u32, 5));
+        assert!(cores[1].append_monotonic( 5_u32, 6));
+
+        let mut expected: Series<10, u32, i32> = Series::new(0);
+
+        for (at, value) in [(1, 10), (2, 10), (4, 5), (5, 6), (7, 1), (9, 1)] {
+            assert!(expected.append_monotonic(at, value));
+        }
+
+        let fused = Series::merge_all(cores, 0).unwrap();
+
+        assert_eq!(fused, expected);
+        assert_eq!(fused.len(), 4);
+    }
+
+    #[test]
+    fn merge_all_counts_overflow() {
+        let mut cores: [Series<2, u32, i32>; 3] = [Series::new(0), Series::new(0), Series::new(0)];
+
+        assert!(cores[0].append_monotonic( 1_u32, 10));
+        assert!(cores[1].append_monotonic( 2_u32, 20));
+        assert!(cores[2].append_monotonic( 3_u32, 30));
+        assert!(cores[2].append_monotonic( 4_u32, 30));
+
+        assert_eq!(Series::merge_all(cores, 0), Err(2));
+    }
+
+    #[test]
+    fn count_in_window() {
+        let mut timeseries: Series<10, u32, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 2_u32, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_u32, 32.7f32));
+        assert!(timeseries.append_monotonic( 8_u32, 12.3f32));
+        assert!(timeseries.append_monotonic( 12_u32, 2.3f32));
+
+        assert_eq!(timeseries.count_in(&5, &7), 0);
+        assert_eq!(timeseries.count_in(&13, &20), 0);
+        assert_eq!(timeseries.count_in(&4, &8), 2);
+        assert_eq!(timeseries.count_in(&9, &12), 1);
+        assert_eq!(timeseries.count_in(&0, &20), 3);
+        assert_eq!(timeseries.count_in(&7, &5), 0);
+    }
+
+    #[test]
+    fn strict_rejects_equal_timestamps() {
+        let mut timeseries: Series<10, u32, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u32, 32.6f32));
+        assert!(!timeseries.append_monotonic( 1_u32, 32.5f32));
+        assert!(!timeseries.append_monotonic( 1_u32, 12.3f32));
+        assert_eq!(timeseries.would_merge(&1, &32.5), MergeOutcome::Reject);
+    }
+
+    #[test]
+    fn non_strict_accepts_equal_timestamps() {
+        let mut timeseries: Series<10, u32, f32> = Series::new_non_strict(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u32, 32.6f32));
+        assert!(timeseries.append_monotonic( 1_u32, 32.5f32));
+        assert_eq!(timeseries.len(), 1);
+        assert_eq!(timeseries.ends_at(), Some(&1));
+        assert_eq!(timeseries.value_at(&1), Some(&32.6));
+
+        assert_eq!(timeseries.would_merge(&1, &12.3), MergeOutcome::Merge);
+        assert!(timeseries.append_monotonic( 1_u32, 12.3f32));
+        assert_eq!(timeseries.value_at(&1), Some(&12.3));
+        assert_eq!(timeseries.len(), 1);
+
+        assert!(timeseries.append_monotonic( 3_u32, 12.3f32));
+        assert!(timeseries.append_monotonic( 3_u32, 2.3f32));
+        assert!(!timeseries.append_monotonic( 2_u32, 2.3f32));
+
+        let mut expected: Series<10, u32, f32> = Series::new(0.3f32);
+        expected.buckets.push(SerieEntry { range: Range { start: 1, end: Some(3) }, value: 12.3 }).unwrap();
+        expected.buckets.push(SerieEntry { range: Range { start: 3, end: None }, value: 2.3 }).unwrap();
+
+        assert_eq!(timeseries.as_slice(), expected.as_slice());
+        assert_eq!(timeseries.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn transitions_on_multi_step_signal() {
+        let mut timeseries: Series<10, u32, f32> = Series::new(0.3f32);
+
+        assert_eq!(timeseries.transitions(), 0);
+
+        for (at, value) in [(1, 32.6f32), (2, 32.5), (3, 31.0), (4, 21.0), (5, 20.5), (6, 20.0)] {
+            assert!(timeseries.append_monotonic(at, value));
+        }
+
+        assert_eq!(timeseries.len(), 5);
+        assert_eq!(timeseries.transitions(), 4);
+        assert_eq!(timeseries.transitions_exceeding(&0.3), 4);
+        assert_eq!(timeseries.transitions_exceeding(&1.0), 2);
+        assert_eq!(timeseries.transitions_exceeding(&10.0), 0);
+    }
+
+    #[test]
+    fn windows2_yields_consecutive_pairs() {
+        let mut timeseries: Series<10, u32, f32> = Series::new(0.3f32);
+
+        assert_eq!(timeseries.windows2().count(), 0);
+
+        assert!(timeseries.append_monotonic( 1_u32, 32.6f32));
+        assert_eq!(timeseries.windows2().count(), 0);
+
+        assert!(timeseries.append_monotonic( 4_u32, 12.3f32));
+        assert!(timeseries.append_monotonic( 6_u32, 2.3f32));
+
+        let pairs: Vec<(u32, u32), 4> = timeseries.windows2().map(|(prev, next)| (prev.range.start, next.range.start)).collect();
+
+        assert_eq!(pairs.as_slice(), &[(1, 4), (4, 6)]);
+    }
+
+    #[test]
+    fn try_from_slice_builds_series() {
+        let timeseries: Series<2, u32, f32> = Series::try_from_slice(0.3, &[(1, 32.6), (2, 32.5), (3, 12.3)]).unwrap();
+
+        assert_eq!(timeseries.len(), 2);
+        assert_eq!(timeseries.ends_at(), Some(&3));
+    }
+
+    #[test]
+    fn try_from_slice_errors() {
+        assert_eq!(Series::<2, u32, f32>::try_from_slice(0.3, &[]), Err(BuildError::Empty));
+        assert_eq!(
+            Series::<2, u32, f32>::try_from_slice(0.3, &[(1, 32.6), (3, 12.3), (2, 2.3)]),
+            Err(BuildError::NonMonotonic { at_index: 2 })
+        );
+        assert_eq!(
+            Series::<2, u32, f32>::try_from_slice(0.3, &[(1, 32.6), (2, 12.3), (3, 2.3)]),
+            Err(BuildError::Capacity { at_index: 2 })
+        );
+        assert_eq!(BuildError::NonMonotonic { at_index: 2 }.to_string(), "point 2 is not later than the previous point");
+    }
+
+    #[test]
+    fn coverage_of_window() {
+        let mut timeseries: Series<10, u32, f32> = Series::new(0.3f32);
+
+        assert_eq!(timeseries.coverage(&0, &10), 0.0);
+
+        assert!(timeseries.append_monotonic( 5_u32, 32.6f32));
+        assert!(timeseries.append_monotonic( 15_u32, 32.6f32));
+        assert!(timeseries.append_monotonic( 17_u32, 12.3f32));
+
+        assert_eq!(timeseries.coverage(&0, &10), 0.5);
+        assert_eq!(timeseries.coverage(&6, &14), 1.0);
+        assert_eq!(timeseries.coverage(&10, &20), 0.5);
+        assert_eq!(timeseries.coverage(&16, &20), 0.0);
+        assert_eq!(timeseries.coverage(&10, &10), 0.0);
+    }
+
+    #[test]
+    fn append_evicting_returning_hands_back_oldest() {
+        let mut timeseries: Series<2, u32, f32> = Series::new(0.3f32);
+
+        assert_eq!(timeseries.append_evicting_returning( 1_u32, 32.6f32), Ok(None));
+        assert_eq!(timeseries.append_evicting_returning( 2_u32, 32.5f32), Ok(None));
+        assert_eq!(timeseries.append_evicting_returning( 3_u32, 12.3f32), Ok(None));
+        assert_eq!(timeseries.append_evicting_returning( 4_u32, 12.2f32), Ok(None));
+        assert_eq!(timeseries.append_evicting_returning( 4_u32, 2.3f32), Err(()));
+
+        assert_eq!(
+            timeseries.append_evicting_returning( 5_u32, 2.3f32),
+            Ok(Some(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 32.6 }))
+        );
+        assert_eq!(
+            timeseries.append_evicting_returning( 6_u32, -7.7f32),
+            Ok(Some(SerieEntry { range: Range { start: 3, end: Some(4) }, value: 12.3 }))
+        );
+
+        assert_eq!(timeseries.len(), 2);
+        assert_eq!(timeseries.starts_at(), Some(&5));
+        assert_eq!(timeseries.ends_at(), Some(&6));
+    }
+
+    #[test]
+    fn slope_at_on_ramp_and_descent() {
+        let mut timeseries: Series<10, u8, f32> = Series::new_asym(0.3f32, 0.3f32);
+
+        assert!(timeseries.append_monotonic( 0_u8, 10.0f32));
+        assert!(timeseries.append_monotonic( 2_u8, 10.0f32));
+        assert!(timeseries.append_monotonic( 6_u8, 20.0f32));
+        assert!(timeseries.append_monotonic( 8_u8, 14.0f32));
+        assert!(timeseries.append_monotonic( 12_u8, 14.0f32));
+
+        assert_eq!(timeseries.slope_at(&1), Some(2.5));
+        assert_eq!(timeseries.slope_at(&6), Some(-3.0));
+        assert_eq!(timeseries.slope_at(&4), None);
+        assert_eq!(timeseries.slope_at(&10), None);
+        assert_eq!(timeseries.slope_at(&13), None);
+    }
+
+    #[test]
+    fn slope_at_u32_timestamps_f32_values() {
+        let mut timeseries: Series<10, u32, f32> = Series::new_asym(0.3f32, 0.3f32);
+
+        assert!(timeseries.append_monotonic( 100_000_u32, 10.0f32));
+        assert!(timeseries.append_monotonic( 100_004_u32, 20.0f32));
+
+        assert_eq!(timeseries.slope_at(&100_000), Some(2.5));
+    }
+
+    #[test]
+    fn value_at_with_linear_u32_timestamps_f32_values() {
+        let mut timeseries: Series<10, u32, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 70_000, end: None }, value: 10.0 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 70_004, end: None }, value: 30.0 }).unwrap();
+
+        assert_eq!(timeseries.value_at_with(&70_001, GapFill::Linear), Some(15.0));
+    }
+
+    #[test]
+    fn slope_at_duration_timestamps_per_second() {
+        let mut timeseries: Series<10, Duration, f64> = Series::new_asym(0.3f64, 0.3f64);
+
+        assert!(timeseries.append_monotonic( Duration::from_millis(500), 1.0f64));
+        assert!(timeseries.append_monotonic( Duration::from_millis(1000), 2.0f64));
+
+        assert_eq!(timeseries.slope_at(&Duration::from_millis(500)), Some(2.0));
+    }
+
+    #[test]
+    fn trend_classification() {
+        // Symmetric thresholds, since the default float mode would merge every rise into the
+        // first bucket.
+        let build = |values: &[f32]| {
+            let mut timeseries: Series<10, u32, f32> = Series::new_asym(0.3f32, 0.3f32);
+
+            for (at, value) in (0_u32..).zip(values) {
+                assert!(timeseries.append_monotonic(at, *value));
+            }
+
+            timeseries
+        };
+
+        assert!(build(&[]).is_flat());
+        assert!(build(&[32.6, 32.8, 32.4]).is_flat());
+        assert_eq!(build(&[32.6, 32.8, 32.4]).trend(), Trend::Flat);
+        assert_eq!(build(&[10.0, 12.0, 15.0]).trend(), Trend::Increasing);
+        assert_eq!(build(&[15.0, 12.0, 10.0]).trend(), Trend::Decreasing);
+        assert_eq!(build(&[10.0, 15.0, 12.0]).trend(), Trend::Mixed);
+        assert!(!build(&[10.0, 15.0, 12.0]).is_flat());
+    }
+
+    #[test]
+    fn new_relative_scales_with_bucket_value() {
+        let mut timeseries: Series<10, u8, f32> = Series::new_relative(0.1f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 100.0f32));
+        assert!(timeseries.append_monotonic( 2_u8, 91.0f32));
+        assert!(timeseries.append_monotonic( 3_u8, 111.0f32));
+        assert!(timeseries.append_monotonic( 4_u8, 1.0f32));
+        assert!(timeseries.append_monotonic( 5_u8, 1.05f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 10> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 100.0 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 3, end: None }, value: 111.0 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 4, end: Some(5) }, value: 1.0 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn new_relative_zero_bucket_deviates_on_any_change() {
+        let mut timeseries: Series<10, u8, f32> = Series::new_relative(0.5f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 0.0f32));
+        assert!(timeseries.append_monotonic( 2_u8, 0.0f32));
+        assert!(timeseries.append_monotonic( 3_u8, 0.001f32));
+
+        assert_eq!(timeseries.len(), 2);
+    }
+
+    #[test]
+    fn new_sdt_splits_slow_drift() {
+        let mut sdt: Series<10, u8, f32> = Series::new_sdt(0.5f32);
+        let mut plain: Series<10, u8, f32> = Series::new_asym(0.5f32, 0.5f32);
+
+        for (at, value) in [(1_u8, 10.0f32), (2, 10.5), (3, 11.0), (4, 11.5), (5, 12.0)] {
+            assert!(sdt.append_monotonic(at, value));
+            assert!(plain.append_monotonic(at, value));
+        }
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 10> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(3) }, value: 10.5 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 4, end: Some(5) }, value: 11.75 }).unwrap();
+
+        assert_eq!(sdt.buckets, expected);
+        assert_eq!(plain.len(), 3);
+    }
+
+    #[test]
+    fn new_sdt_isolates_nan() {
+        let mut timeseries: Series<10, u8, f32> = Series::new_sdt(0.5f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 1.0f32));
+        assert!(timeseries.append_monotonic( 2_u8, f32::NAN));
+        assert!(timeseries.append_monotonic( 3_u8, 1.0f32));
+
+        assert_eq!(timeseries.len(), 3);
+    }
+
+    #[test]
+    fn hysteresis_splits_rises_unlike_new() {
+        let readings = [(1_u8, 10.0f32), (2, 11.2), (3, 11.3)];
+        let mut plain: Series<10, u8, f32> = Series::new(1.0f32);
+        let mut timeseries: Series<10, u8, f32> = Series::new_hysteresis(1.0f32, 0.5f32);
+
+        for (at, value) in readings {
+            assert!(plain.append_monotonic(at, value));
+            assert!(timeseries.append_monotonic(at, value));
+        }
+
+        assert_eq!(plain.len(), 1);
+        assert_eq!(timeseries.len(), 2);
     }
 }
@@ -0,0 +1,75 @@
+/// The tolerance [`assert_series_approx_eq!`](crate::assert_series_approx_eq) uses when none
+/// is given.
+pub trait ApproxEpsilon {
+    fn epsilon() -> Self;
+}
+
+impl ApproxEpsilon for f32 {
+    fn epsilon() -> Self {
+        1e-5
+    }
+}
+
+impl ApproxEpsilon for f64 {
+    fn epsilon() -> Self {
+        1e-9
+    }
+}
+
+/// Asserts that two series have identical bucket ranges and bucket values within a tolerance,
+/// using [`Series::approx_eq`](crate::timeseries::Series::approx_eq). Without an explicit
+/// tolerance the value type's [`ApproxEpsilon`] is used.
+#[macro_export]
+macro_rules! assert_series_approx_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_series_approx_eq!($left, $right, $crate::timeseries::ApproxEpsilon::epsilon())
+    };
+    ($left:expr, $right:expr, $tolerance:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !left.approx_eq(right, &$tolerance) {
+                    panic!("assertion failed: series are not approximately equal\n  left: {:?}\n right: {:?}", left, right)
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::timeseries::Series;
+
+    #[test]
+    fn passes_for_epsilon_close_series() {
+        let mut a: Series<10, u8, f32> = Series::new(0.3f32);
+        let mut b: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(a.append_monotonic( 1_u8, 0.1f32 + 0.2f32));
+        assert!(b.append_monotonic( 1_u8, 0.3f32));
+
+        assert_series_approx_eq!(a, b);
+    }
+
+    #[test]
+    fn passes_with_explicit_tolerance() {
+        let mut a: Series<10, u8, f64> = Series::new(0.3f64);
+        let mut b: Series<10, u8, f64> = Series::new(0.3f64);
+
+        assert!(a.append_monotonic( 1_u8, 32.6f64));
+        assert!(b.append_monotonic( 1_u8, 32.62f64));
+
+        assert_series_approx_eq!(a, b, 0.05);
+    }
+
+    #[test]
+    #[should_panic(expected = "series are not approximately equal")]
+    fn fails_beyond_epsilon() {
+        let mut a: Series<10, u8, f32> = Series::new(0.3f32);
+        let mut b: Series<10, u8, f32> = Series::new(0.3f32);
+
+        assert!(a.append_monotonic( 1_u8, 32.6f32));
+        assert!(b.append_monotonic( 1_u8, 32.61f32));
+
+        assert_series_approx_eq!(a, b);
+    }
+}
@@ -0,0 +1,235 @@
+use core::cmp::Ordering;
+use core::ops::Sub;
+
+use super::{Comparator, Config, Deviate, DeviateInf, DeviateL1, DeviateRelative, Metric, Mode, Series, SpanLimit, SwingingDoor};
+
+/// Configures how a [`Series`] compresses incoming readings. A builder starts out equivalent
+/// to [`Series::new`]; each method switches on one behavior. The methods choosing how values
+/// are compared ([`SeriesBuilder::asymmetric`], [`SeriesBuilder::hysteresis`],
+/// [`SeriesBuilder::metric`], [`SeriesBuilder::relative`] and [`SeriesBuilder::sdt`]) replace
+/// each other, while the remaining ones combine with any of them.
+#[derive(Debug)]
+pub struct SeriesBuilder<const N : usize, I, T> {
+    max_deviation: T,
+    config: Config<I, T>
+}
+
+impl <const N : usize, I : Ord, T : Deviate> SeriesBuilder<N, I, T> {
+    pub fn new(max_deviation: T) -> SeriesBuilder<N, I, T> {
+        SeriesBuilder { max_deviation, config: Config::default() }
+    }
+
+    /// Sets the threshold, replacing any asymmetric thresholds set earlier. Other comparisons
+    /// keep applying with the new threshold.
+    pub fn deviation(mut self, max_deviation: T) -> SeriesBuilder<N, I, T> {
+        self.max_deviation = max_deviation;
+
+        if let Mode::Asymmetric { .. } = self.config.mode {
+            self.config.mode = Mode::Absolute;
+        }
+
+        self
+    }
+
+    /// Treats the threshold as a fraction of the bucket's value, as [`Series::new_relative`]
+    /// does.
+    pub fn relative(mut self) -> SeriesBuilder<N, I, T> where T: DeviateRelative {
+        self.config.mode = Mode::relative();
+        self
+    }
+
+    /// Uses separate thresholds for rises and drops, as [`Series::new_asym`] does.
+    pub fn asymmetric(mut self, up: T, down: T) -> SeriesBuilder<N, I, T> {
+        self.max_deviation = up;
+        self.config.mode = Mode::Asymmetric { down };
+        self
+    }
+
+    /// Lets a split be undone by a reading that returns within `close` of the previous bucket,
+    /// as [`Series::new_hysteresis`] does with the threshold as its open threshold.
+    pub fn hysteresis(mut self, close: T) -> SeriesBuilder<N, I, T> {
+        self.config.mode = Mode::Hysteresis { close };
+        self
+    }
+
+    /// Combines the channels of multi-channel values with `metric`, as [`Series::new_metric`]
+    /// does.
+    pub fn metric(mut self, metric: Metric) -> SeriesBuilder<N, I, T> where T: DeviateL1 + DeviateInf {
+        self.config.mode = Mode::norm(metric);
+        self
+    }
+
+    /// Uses a swinging door, as [`Series::new_sdt`] does.
+    pub fn sdt(mut self) -> SeriesBuilder<N, I, T> where T: SwingingDoor {
+        self.config.mode = Mode::sdt();
+        self
+    }
+
+    /// Orders timestamps with `cmp` instead of `I: Ord`, as [`Series::new_with_cmp`] does.
+    pub fn cmp(mut self, cmp: fn(&I, &I) -> Ordering) -> SeriesBuilder<N, I, T> {
+        self.config.order = Some(Comparator { cmp });
+        self
+    }
+
+    /// Also accepts readings at the same timestamp as the last one, as
+    /// [`Series::new_non_strict`] does.
+    pub fn non_strict(mut self) -> SeriesBuilder<N, I, T> {
+        self.config.strict = false;
+        self
+    }
+
+    /// Closes a bucket once it would span more than `max_span`, as
+    /// [`Series::new_with_max_span`] does.
+    pub fn max_span(mut self, max_span: I) -> SeriesBuilder<N, I, T> where I: Copy + Sub<Output = I> {
+        self.config.max_span = Some(SpanLimit::new(max_span));
+        self
+    }
+
+    /// Makes a full series drop its oldest bucket to make room for a new one instead of
+    /// rejecting the append.
+    pub fn evicting(mut self) -> SeriesBuilder<N, I, T> {
+        self.config.evicting = true;
+        self
+    }
+
+    pub fn build(self) -> Series<N, I, T> {
+        Series::configured(self.max_deviation, self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Debug;
+
+    use heapless::Vec;
+
+    use super::*;
+    use crate::timeseries::{Range, SerieEntry};
+
+    #[test]
+    fn builder_defaults_to_new() {
+        let built: Series<10, u8, f32> = SeriesBuilder::new(0.3f32).build();
+        assert_eq!(built, Series::new(0.3f32));
+    }
+
+    #[test]
+    fn builder_deviation_overrides_asymmetric() {
+        let built: Series<10, u8, f32> = SeriesBuilder::new(0.1f32).asymmetric(0.5, 2.0).deviation(0.3).build();
+        assert_eq!(built, Series::new(0.3f32));
+    }
+
+    #[test]
+    fn builder_asymmetric_matches_new_asym() {
+        let mut built: Series<10, u8, f32> = SeriesBuilder::new(0.3f32).asymmetric(0.5, 2.0).build();
+        let mut direct: Series<10, u8, f32> = Series::new_asym(0.5, 2.0);
+
+        for (at, value) in [(1_u8, 32.0f32), (2, 31.0), (3, 33.0), (4, 29.0)] {
+            assert_eq!(built.append_monotonic(at, value), direct.append_monotonic(at, value));
+        }
+
+        assert_eq!(built, direct);
+    }
+
+    #[test]
+    fn builder_evicting_drops_oldest_bucket() {
+        let mut timeseries: Series<2, u8, f32> = SeriesBuilder::new(0.3f32).evicting().build();
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 22.6f32));
+        assert!(timeseries.append_monotonic( 3_u8, 22.7f32));
+        assert!(timeseries.append_monotonic( 4_u8, 12.6f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 2> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 2, end: Some(3) }, value: 22.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 4, end: None }, value: 12.6 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    fn assert_same<const N : usize, I : Ord + Copy + Debug, T : Deviate + PartialEq + Copy + Debug>(mut built: Series<N, I, T>, mut direct: Series<N, I, T>, points: &[(I, T)]) {
+        for &(at, value) in points {
+            assert_eq!(built.append_monotonic(at, value), direct.append_monotonic(at, value));
+        }
+
+        assert_eq!(built, direct);
+    }
+
+    const DRIFT: [(u8, f32); 6] = [(1, 10.0), (2, 10.2), (3, 10.4), (3, 9.0), (4, 12.0), (5, 11.9)];
+
+    #[test]
+    fn builder_relative_matches_new_relative() {
+        let built: Series<10, u8, f32> = SeriesBuilder::new(0.05f32).relative().build();
+        assert_same(built, Series::new_relative(0.05f32), &DRIFT);
+    }
+
+    #[test]
+    fn builder_sdt_matches_new_sdt() {
+        let built: Series<10, u8, f32> = SeriesBuilder::new(0.3f32).sdt().build();
+        assert_same(built, Series::new_sdt(0.3f32), &DRIFT);
+    }
+
+    #[test]
+    fn builder_hysteresis_matches_new_hysteresis() {
+        let built: Series<10, u8, f32> = SeriesBuilder::new(0.5f32).hysteresis(0.2).build();
+        assert_same(built, Series::new_hysteresis(0.5f32, 0.2f32), &[(1, 20.0), (2, 20.6), (3, 20.1), (4, 20.7), (5, 21.0)]);
+    }
+
+    #[test]
+    fn builder_metric_matches_new_metric() {
+        let built: Series<10, u8, [f32; 2]> = SeriesBuilder::new([1.0, 1.0]).metric(Metric::L1).build();
+        let points = [(1_u8, [0.0, 0.0]), (2, [0.6, 0.6]), (3, [0.9, 0.0])];
+
+        assert_same(built, Series::new_metric([1.0f32, 1.0], Metric::L1), &points);
+    }
+
+    fn wrapping_cmp(a: &u16, b: &u16) -> Ordering {
+        (a.wrapping_sub(*b) as i16).cmp(&0)
+    }
+
+    #[test]
+    fn builder_cmp_matches_new_with_cmp() {
+        let built: Series<10, u16, f32> = SeriesBuilder::new(0.3f32).cmp(wrapping_cmp).build();
+        let points = [(65500_u16, 1.0f32), (0, 1.1), (100, 5.0), (65000, 5.0)];
+
+        assert_same(built, Series::new_with_cmp(0.3f32, wrapping_cmp), &points);
+    }
+
+    #[test]
+    fn builder_non_strict_matches_new_non_strict() {
+        let built: Series<10, u8, f32> = SeriesBuilder::new(0.3f32).non_strict().build();
+        assert_same(built, Series::new_non_strict(0.3f32), &DRIFT);
+    }
+
+    #[test]
+    fn builder_max_span_matches_new_with_max_span() {
+        let built: Series<10, u8, f32> = SeriesBuilder::new(0.3f32).max_span(2).build();
+        let points = [(1_u8, 5.0f32), (2, 5.0), (3, 5.0), (4, 5.0), (7, 5.0)];
+
+        assert_same(built, Series::new_with_max_span(0.3f32, 2), &points);
+    }
+
+    #[test]
+    fn builder_combines_mode_and_flags() {
+        let mut timeseries: Series<2, u8, f32> = SeriesBuilder::new(0.3f32).sdt().max_span(3).evicting().build();
+
+        for at in 1..=6_u8 {
+            assert!(timeseries.append_monotonic(at, 5.0));
+        }
+
+        assert!(timeseries.append_monotonic( 7_u8, 9.0f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 2> = Vec::new();
+
+        expected.push(SerieEntry { range: Range { start: 5, end: Some(6) }, value: 5.0 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 7, end: None }, value: 9.0 }).unwrap();
+
+        assert_eq!(timeseries.buckets, expected)
+    }
+
+    #[test]
+    fn builder_deviation_keeps_relative() {
+        let built: Series<10, u8, f32> = SeriesBuilder::new(0.3f32).relative().deviation(0.05).build();
+        assert_same(built, Series::new_relative(0.05f32), &DRIFT);
+    }
+}
@@ -0,0 +1,119 @@
+use heapless::Vec;
+
+use super::{Deviate, Series};
+
+/// A [`Series`] that also records how many raw samples each bucket absorbed, starting at one
+/// when a bucket is created and incrementing every time a point merges into it. A count
+/// saturates at `u32::MAX` instead of overflowing.
+#[derive(Debug, Eq, PartialEq)]
+pub struct CountedSeries<const N : usize, I, T> {
+    series: Series<N, I, T>,
+    counts: Vec<u32, N>
+}
+
+impl <const N : usize, I : Ord, T : Deviate> Series<N, I, T> {
+    pub fn new_counted(max_deviation: T) -> CountedSeries<N, I, T> {
+        CountedSeries { series: Series::new(max_deviation), counts: Vec::new() }
+    }
+}
+
+impl <const N : usize, I : Ord, T : Deviate> CountedSeries<N, I, T> {
+    pub fn append_monotonic(&mut self, at: I, value: T) -> bool where T: PartialEq {
+        let len = self.series.buckets.len();
+
+        if !self.series.append_monotonic(at, value) {
+            return false
+        }
+
+        match self.counts.last_mut() {
+            Some(count) if self.series.buckets.len() == len => *count = count.saturating_add(1),
+            _ => {
+                let _ = self.counts.push(1);
+            }
+        }
+
+        true
+    }
+
+    pub fn series(&self) -> &Series<N, I, T> {
+        &self.series
+    }
+
+    /// The number of samples absorbed by each bucket, in bucket order.
+    pub fn counts(&self) -> &[u32] {
+        &self.counts
+    }
+
+    /// The mean of all absorbed samples, weighting each bucket's value by its sample count.
+    pub fn mean(&self) -> Option<f64> where T: Copy + Into<f64> {
+        let total: f64 = self.counts.iter().map(|count| f64::from(*count)).sum();
+
+        if total == 0.0 {
+            return None
+        }
+
+        let sum: f64 = self.series.buckets.iter().zip(&self.counts)
+            .map(|(b, count)| b.value.into() * f64::from(*count))
+            .sum();
+
+        Some(sum / total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_after_merges() {
+        let mut timeseries: CountedSeries<10, u8, f32> = Series::new_counted(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 3_u8, 32.5f32));
+
+        assert_eq!(timeseries.counts(), [3]);
+    }
+
+    #[test]
+    fn counts_after_split() {
+        let mut timeseries: CountedSeries<10, u8, f32> = Series::new_counted(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 32.7f32));
+        assert!(timeseries.append_monotonic( 3_u8, 12.5f32));
+        assert!(!timeseries.append_monotonic( 3_u8, 12.5f32));
+
+        assert_eq!(timeseries.counts(), [2, 1]);
+        assert_eq!(timeseries.series().buckets.len(), 2);
+    }
+
+    #[test]
+    fn mean_weights_by_count() {
+        let mut timeseries: CountedSeries<10, u8, f32> = Series::new_counted(0.3f32);
+
+        assert_eq!(timeseries.mean(), None);
+
+        assert!(timeseries.append_monotonic( 1_u8, 30.0f32));
+        assert!(timeseries.append_monotonic( 2_u8, 30.1f32));
+        assert!(timeseries.append_monotonic( 3_u8, 30.2f32));
+        assert!(timeseries.append_monotonic( 4_u8, 10.0f32));
+
+        assert_eq!(timeseries.mean(), Some(25.0));
+    }
+
+    #[test]
+    fn counts_saturate() {
+        let mut timeseries: CountedSeries<10, u8, f32> = Series::new_counted(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 30.0f32));
+        timeseries.counts[0] = u32::MAX;
+
+        assert!(timeseries.append_monotonic( 2_u8, 30.0f32));
+        assert!(timeseries.append_monotonic( 3_u8, 10.0f32));
+        timeseries.counts[1] = u32::MAX;
+
+        assert_eq!(timeseries.counts(), [u32::MAX, u32::MAX]);
+        assert_eq!(timeseries.mean(), Some(20.0));
+    }
+}
@@ -0,0 +1,63 @@
+use super::{Deviate, Series};
+
+/// A [`Series`] indexed by sample number instead of by time: each pushed value is stamped
+/// with the next value of an internal counter, starting at zero.
+#[derive(Debug, Eq, PartialEq)]
+pub struct CountingSeries<const N : usize, T> {
+    series: Series<N, usize, T>,
+    next: usize
+}
+
+impl <const N : usize, T : Deviate> CountingSeries<N, T> {
+    pub fn new(max_deviation: T) -> CountingSeries<N, T> {
+        CountingSeries { series: Series::new(max_deviation), next: 0 }
+    }
+
+    /// Appends `value` at the next sample number. The counter advances even when the value is
+    /// rejected, so timestamps keep matching the number of values pushed.
+    pub fn push(&mut self, value: T) -> bool where T: PartialEq {
+        let at = self.next;
+        self.next += 1;
+
+        self.series.append_monotonic(at, value)
+    }
+
+    pub fn series(&self) -> &Series<N, usize, T> {
+        &self.series
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use heapless::Vec;
+
+    use super::*;
+    use crate::timeseries::{Range, SerieEntry};
+
+    #[test]
+    fn stamps_sequentially() {
+        let mut timeseries: CountingSeries<10, f32> = CountingSeries::new(0.3f32);
+
+        assert!(timeseries.push(32.6f32));
+        assert!(timeseries.push(32.7f32));
+        assert!(timeseries.push(32.5f32));
+        assert!(timeseries.push(12.3f32));
+
+        let mut expected: Vec<SerieEntry<usize, f32>, 2> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 0, end: Some(2) }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 3, end: None }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.series().buckets, expected);
+    }
+
+    #[test]
+    fn counter_advances_on_rejection() {
+        let mut timeseries: CountingSeries<1, f32> = CountingSeries::new(0.3f32);
+
+        assert!(timeseries.push(32.6f32));
+        assert!(!timeseries.push(12.3f32));
+
+        assert_eq!(timeseries.series().ends_at(), Some(&0));
+        assert_eq!(timeseries.next, 2);
+    }
+}
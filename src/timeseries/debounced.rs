@@ -0,0 +1,110 @@
+use super::{Deviate, MergeOutcome, Series};
+
+/// A [`Series`] that ignores lone spikes. A reading that would open a new bucket is held back
+/// until the next one arrives: the split is only committed when that reading deviates as well,
+/// otherwise the held reading is dropped as noise.
+#[derive(Debug, Eq, PartialEq)]
+pub struct DebouncedSeries<const N : usize, I, T> {
+    series: Series<N, I, T>,
+    pending: Option<(I, T)>
+}
+
+impl <const N : usize, I : Ord, T : Deviate + PartialEq> DebouncedSeries<N, I, T> {
+    pub fn new(max_deviation: T) -> DebouncedSeries<N, I, T> {
+        DebouncedSeries { series: Series::new(max_deviation), pending: None }
+    }
+
+    /// Appends like [`Series::append_monotonic`], except that a deviating reading is only held
+    /// until the next call. Returns `false` when the reading is rejected, which includes a
+    /// reading that is not later than the held one.
+    pub fn append_debounced(&mut self, at: I, value: T) -> bool {
+        if self.pending.as_ref().map(|(held, _)| at <= *held).unwrap_or(false) {
+            return false
+        }
+
+        let splits = !self.series.is_empty() && self.series.would_merge(&at, &value) == MergeOutcome::NewBucket;
+
+        match self.pending.take() {
+            Some((held, held_value)) if splits => {
+                self.series.append_monotonic(held, held_value);
+                self.series.append_monotonic(at, value)
+            },
+            None if splits => {
+                self.pending = Some((at, value));
+                true
+            },
+            _ => self.series.append_monotonic(at, value)
+        }
+    }
+
+    /// The deviating reading waiting for the next one to confirm it.
+    pub fn pending(&self) -> Option<&(I, T)> {
+        self.pending.as_ref()
+    }
+
+    /// Commits the held reading, if any, as if it had been confirmed.
+    pub fn flush(&mut self) -> bool {
+        match self.pending.take() {
+            Some((at, value)) => self.series.append_monotonic(at, value),
+            None => false
+        }
+    }
+
+    pub fn series(&self) -> &Series<N, I, T> {
+        &self.series
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use heapless::Vec;
+
+    use super::*;
+    use crate::timeseries::{Range, SerieEntry};
+
+    #[test]
+    fn drops_single_spike() {
+        let mut timeseries: DebouncedSeries<10, u32, f32> = DebouncedSeries::new(0.3f32);
+
+        assert!(timeseries.append_debounced( 1_u32, 32.6f32));
+        assert!(timeseries.append_debounced( 2_u32, 12.3f32));
+        assert_eq!(timeseries.pending(), Some(&(2, 12.3)));
+
+        assert!(timeseries.append_debounced( 3_u32, 32.5f32));
+        assert_eq!(timeseries.pending(), None);
+
+        let mut expected: Vec<SerieEntry<u32, f32>, 1> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1, end: Some(3) }, value: 32.6 }).unwrap();
+
+        assert_eq!(timeseries.series().buckets, expected);
+    }
+
+    #[test]
+    fn splits_on_sustained_change() {
+        let mut timeseries: DebouncedSeries<10, u32, f32> = DebouncedSeries::new(0.3f32);
+
+        assert!(timeseries.append_debounced( 1_u32, 32.6f32));
+        assert!(timeseries.append_debounced( 2_u32, 12.3f32));
+        assert!(timeseries.append_debounced( 3_u32, 12.2f32));
+        assert!(!timeseries.append_debounced( 3_u32, 12.2f32));
+
+        let mut expected: Vec<SerieEntry<u32, f32>, 2> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 1, end: None }, value: 32.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 2, end: Some(3) }, value: 12.3 }).unwrap();
+
+        assert_eq!(timeseries.series().buckets, expected);
+    }
+
+    #[test]
+    fn flush_commits_pending() {
+        let mut timeseries: DebouncedSeries<10, u32, f32> = DebouncedSeries::new(0.3f32);
+
+        assert!(timeseries.append_debounced( 1_u32, 32.6f32));
+        assert!(timeseries.append_debounced( 2_u32, 12.3f32));
+        assert!(!timeseries.append_debounced( 2_u32, 32.6f32));
+
+        assert!(timeseries.flush());
+        assert!(!timeseries.flush());
+        assert_eq!(timeseries.series().len(), 2);
+    }
+}
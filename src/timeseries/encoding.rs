@@ -0,0 +1,517 @@
+use core::fmt;
+
+use heapless::Vec;
+
+use super::{Deviate, InvariantError, Range, SerieEntry, Series};
+
+/// Marks the start of an encoded series.
+const MAGIC: [u8; 2] = *b"TS";
+
+/// The layout written by [`Series::encode`]: the magic, this version byte, a little-endian
+/// `u16` bucket count and then per bucket a flag byte (`1` when it has an end), the start, the
+/// end when present and the value.
+const VERSION: u8 = 1;
+
+/// The layout written by [`Series::encode_delta`]: like [`VERSION`], except that only the first
+/// start and value are stored in full. Every other start is a varint [`Delta`] from the latest
+/// timestamp of the bucket before it, an end is a delta from its start and a value is a delta
+/// from the value before it.
+const DELTA_VERSION: u8 = 2;
+
+/// A fixed-width little-endian representation used by [`Series::encode`].
+pub trait Encode : Sized {
+    const SIZE: usize;
+
+    /// Writes `Self::SIZE` bytes to the start of `buf`.
+    fn write(&self, buf: &mut [u8]);
+
+    /// Reads `Self::SIZE` bytes from the start of `buf`.
+    fn read(buf: &[u8]) -> Self;
+}
+
+macro_rules! encode_le {
+    ($($t:ty),*) => {$(
+        impl Encode for $t {
+            const SIZE: usize = core::mem::size_of::<$t>();
+
+            fn write(&self, buf: &mut [u8]) {
+                buf[..Self::SIZE].copy_from_slice(&self.to_le_bytes());
+            }
+
+            fn read(buf: &[u8]) -> Self {
+                let mut bytes = [0; core::mem::size_of::<$t>()];
+                bytes.copy_from_slice(&buf[..Self::SIZE]);
+                <$t>::from_le_bytes(bytes)
+            }
+        }
+    )*}
+}
+
+encode_le!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+/// A compact difference between consecutive values, used by [`Series::encode_delta`]. Values
+/// close to each other give small deltas, and applying a delta to the previous value restores
+/// the original exactly.
+pub trait Delta : Encode {
+    fn delta(&self, previous: &Self) -> u64;
+    fn apply(previous: &Self, delta: u64) -> Self;
+}
+
+/// Integers store the wrapping difference, zigzag encoded so that small decreases stay small.
+macro_rules! delta_int {
+    ($($t:ty => $signed:ty),*) => {$(
+        impl Delta for $t {
+            fn delta(&self, previous: &Self) -> u64 {
+                let difference = self.wrapping_sub(*previous) as $signed as i64;
+                ((difference << 1) ^ (difference >> 63)) as u64
+            }
+
+            fn apply(previous: &Self, delta: u64) -> Self {
+                let difference = ((delta >> 1) as i64) ^ -((delta & 1) as i64);
+                previous.wrapping_add(difference as $t)
+            }
+        }
+    )*}
+}
+
+delta_int!(u8 => i8, u16 => i16, u32 => i32, u64 => i64, i8 => i8, i16 => i16, i32 => i32, i64 => i64);
+
+/// Floats store the XOR of their bit patterns, which is small when sign, exponent and the
+/// leading mantissa bits are unchanged.
+macro_rules! delta_float {
+    ($($t:ty => $bits:ty),*) => {$(
+        impl Delta for $t {
+            fn delta(&self, previous: &Self) -> u64 {
+                u64::from(self.to_bits() ^ previous.to_bits())
+            }
+
+            fn apply(previous: &Self, delta: u64) -> Self {
+                <$t>::from_bits(previous.to_bits() ^ delta as $bits)
+            }
+        }
+    )*}
+}
+
+delta_float!(f32 => u32, f64 => u64);
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EncodeError {
+    /// The buffer cannot hold the encoded series.
+    BufferTooSmall,
+    /// The series holds more buckets than the `u16` count of the format can store.
+    TooManyBuckets
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DecodeError {
+    /// The buffer does not start with the magic bytes.
+    BadMagic,
+    /// The buffer was written by an unknown version of the format.
+    UnsupportedVersion(u8),
+    /// The buffer ends before the encoded series does.
+    Truncated,
+    /// The buffer holds more buckets than the series can hold.
+    Capacity,
+    /// The decoded buckets are not a valid series.
+    Invalid(InvariantError)
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::BadMagic => write!(f, "not an encoded series"),
+            DecodeError::UnsupportedVersion(version) => write!(f, "unsupported format version {}", version),
+            DecodeError::Truncated => write!(f, "encoded series is truncated"),
+            DecodeError::Capacity => write!(f, "encoded series exceeds the capacity"),
+            DecodeError::Invalid(error) => write!(f, "encoded series is invalid: {}", error)
+        }
+    }
+}
+
+/// Hands out consecutive chunks of a buffer.
+struct Cursor<'a> {
+    buf: &'a [u8],
+    offset: usize
+}
+
+impl <'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let chunk = self.buf.get(self.offset..self.offset + len).ok_or(DecodeError::Truncated)?;
+        self.offset += len;
+        Ok(chunk)
+    }
+
+    fn read<E: Encode>(&mut self) -> Result<E, DecodeError> {
+        self.take(E::SIZE).map(E::read)
+    }
+
+    /// Reads an unsigned LEB128 varint.
+    fn read_varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value = 0u64;
+
+        for shift in (0..64).step_by(7) {
+            let byte = self.read::<u8>()?;
+            value |= u64::from(byte & 0x7F) << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(value)
+            }
+        }
+
+        Err(DecodeError::Truncated)
+    }
+}
+
+/// Appends to a buffer, failing once it is full.
+struct Writer<'a> {
+    buf: &'a mut [u8],
+    offset: usize
+}
+
+impl <'a> Writer<'a> {
+    fn reserve(&mut self, len: usize) -> Result<&mut [u8], EncodeError> {
+        let chunk = self.buf.get_mut(self.offset..self.offset + len).ok_or(EncodeError::BufferTooSmall)?;
+        self.offset += len;
+        Ok(chunk)
+    }
+
+    fn write<E: Encode>(&mut self, value: &E) -> Result<(), EncodeError> {
+        self.reserve(E::SIZE).map(|chunk| value.write(chunk))
+    }
+
+    /// Writes an unsigned LEB128 varint.
+    fn write_varint(&mut self, mut value: u64) -> Result<(), EncodeError> {
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+
+            if value == 0 {
+                return self.write(&byte)
+            }
+
+            self.write(&(byte | 0x80))?;
+        }
+    }
+}
+
+impl <const N : usize, I : Ord + Encode, T : Deviate + Encode> Series<N, I, T> {
+    /// Writes the buckets to `buf` and returns the number of bytes written. Only the buckets
+    /// are stored; the threshold and compression settings are supplied again when decoding.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let size = 5 + self.buckets.iter()
+            .map(|b| 1 + I::SIZE + T::SIZE + if b.range.end.is_some() { I::SIZE } else { 0 })
+            .sum::<usize>();
+        let count = u16::try_from(self.buckets.len()).map_err(|_| EncodeError::TooManyBuckets)?;
+        let buf = buf.get_mut(..size).ok_or(EncodeError::BufferTooSmall)?;
+
+        buf[..2].copy_from_slice(&MAGIC);
+        buf[2] = VERSION;
+        count.write(&mut buf[3..]);
+
+        let mut offset = 5;
+
+        for b in &self.buckets {
+            buf[offset] = u8::from(b.range.end.is_some());
+            b.range.start.write(&mut buf[offset + 1..]);
+            offset += 1 + I::SIZE;
+
+            if let Some(end) = &b.range.end {
+                end.write(&mut buf[offset..]);
+                offset += I::SIZE;
+            }
+
+            b.value.write(&mut buf[offset..]);
+            offset += T::SIZE;
+        }
+
+        Ok(size)
+    }
+
+    /// Writes the buckets like [`Series::encode`], but stores timestamps and values as varint
+    /// deltas, which is much smaller for slowly changing series. Returns the number of bytes
+    /// written.
+    pub fn encode_delta(&self, buf: &mut [u8]) -> Result<usize, EncodeError> where I: Delta, T: Delta {
+        let count = u16::try_from(self.buckets.len()).map_err(|_| EncodeError::TooManyBuckets)?;
+        let mut writer = Writer { buf, offset: 0 };
+
+        writer.reserve(2)?.copy_from_slice(&MAGIC);
+        writer.write(&DELTA_VERSION)?;
+        writer.write(&count)?;
+
+        for (index, b) in self.buckets.iter().enumerate() {
+            writer.write(&u8::from(b.range.end.is_some()))?;
+
+            match index.checked_sub(1).map(|i| &self.buckets[i]) {
+                Some(previous) => writer.write_varint(b.range.start.delta(previous.range.latest()))?,
+                None => writer.write(&b.range.start)?
+            }
+
+            if let Some(end) = &b.range.end {
+                writer.write_varint(end.delta(&b.range.start))?;
+            }
+
+            match index.checked_sub(1).map(|i| &self.buckets[i]) {
+                Some(previous) => writer.write_varint(b.value.delta(&previous.value))?,
+                None => writer.write(&b.value)?
+            }
+        }
+
+        Ok(writer.offset)
+    }
+
+    /// Reads a series written by [`Series::encode`], validating it like
+    /// [`Series::from_buckets`]. A series written by [`Series::encode_delta`] is reported as
+    /// [`DecodeError::UnsupportedVersion`]; read it with [`Series::decode_delta`].
+    pub fn decode(buf: &[u8], max_deviation: T) -> Result<Series<N, I, T>, DecodeError> {
+        let (mut cursor, version) = header(buf)?;
+
+        let buckets = match version {
+            VERSION => decode_v1(&mut cursor)?,
+            version => return Err(DecodeError::UnsupportedVersion(version))
+        };
+
+        Series::from_buckets(max_deviation, buckets).map_err(DecodeError::Invalid)
+    }
+
+    /// Reads a series written by [`Series::encode`] or [`Series::encode_delta`], validating it
+    /// like [`Series::from_buckets`].
+    pub fn decode_delta(buf: &[u8], max_deviation: T) -> Result<Series<N, I, T>, DecodeError> where I: Delta, T: Delta {
+        let (mut cursor, version) = header(buf)?;
+
+        let buckets = match version {
+            // Every layout keeps its own arm, so series persisted by earlier firmware stay
+            // readable after the default layout changes.
+            VERSION => decode_v1(&mut cursor)?,
+            DELTA_VERSION => decode_v2(&mut cursor)?,
+            version => return Err(DecodeError::UnsupportedVersion(version))
+        };
+
+        Series::from_buckets(max_deviation, buckets).map_err(DecodeError::Invalid)
+    }
+}
+
+/// Checks the magic and returns a cursor past the version byte, together with that version.
+fn header(buf: &[u8]) -> Result<(Cursor<'_>, u8), DecodeError> {
+    let mut cursor = Cursor { buf, offset: 0 };
+
+    if cursor.take(2) != Ok(&MAGIC[..]) {
+        return Err(DecodeError::BadMagic)
+    }
+
+    let version = cursor.read::<u8>()?;
+
+    Ok((cursor, version))
+}
+
+fn decode_v1<const N : usize, I : Encode, T : Encode>(cursor: &mut Cursor<'_>) -> Result<Vec<SerieEntry<I, T>, N>, DecodeError> {
+    let count = cursor.read::<u16>()?;
+    let mut buckets = Vec::new();
+
+    for _ in 0..count {
+        let ranged = cursor.read::<u8>()? != 0;
+        let start = cursor.read()?;
+        let end = if ranged { Some(cursor.read()?) } else { None };
+        let value = cursor.read()?;
+
+        buckets.push(SerieEntry { range: Range { start, end }, value }).map_err(|_| DecodeError::Capacity)?;
+    }
+
+    Ok(buckets)
+}
+
+fn decode_v2<const N : usize, I : Ord + Delta, T : Delta>(cursor: &mut Cursor<'_>) -> Result<Vec<SerieEntry<I, T>, N>, DecodeError> {
+    let count = cursor.read::<u16>()?;
+    let mut buckets: Vec<SerieEntry<I, T>, N> = Vec::new();
+
+    for _ in 0..count {
+        let ranged = cursor.read::<u8>()? != 0;
+        let start = match buckets.last() {
+            Some(previous) => I::apply(previous.range.latest(), cursor.read_varint()?),
+            None => cursor.read()?
+        };
+        let end = if ranged { Some(I::apply(&start, cursor.read_varint()?)) } else { None };
+        let value = match buckets.last() {
+            Some(previous) => T::apply(&previous.value, cursor.read_varint()?),
+            None => cursor.read()?
+        };
+
+        buckets.push(SerieEntry { range: Range { start, end }, value }).map_err(|_| DecodeError::Capacity)?;
+    }
+
+    Ok(buckets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A series with buckets `[1, 4] = 32.6` and `[6] = 12.3`, as written by version 1.
+    const V1_BLOB: [u8; 27] = [
+        b'T', b'S', 1, 2, 0,
+        1, 1, 0, 0, 0, 4, 0, 0, 0, 0x66, 0x66, 0x02, 0x42,
+        0, 6, 0, 0, 0, 0xCD, 0xCC, 0x44, 0x41
+    ];
+
+    fn sample() -> Series<10, u32, f32> {
+        let mut timeseries: Series<10, u32, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u32, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_u32, 32.7f32));
+        assert!(timeseries.append_monotonic( 6_u32, 12.3f32));
+
+        timeseries
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut buf = [0u8; 64];
+        let written = sample().encode(&mut buf).unwrap();
+
+        assert_eq!(buf[..written], V1_BLOB);
+
+        let decoded: Series<10, u32, f32> = Series::decode(&buf[..written], 0.3f32).unwrap();
+
+        assert_eq!(decoded, sample());
+    }
+
+    #[test]
+    fn decodes_v1_blob() {
+        let decoded: Series<10, u32, f32> = Series::decode(&V1_BLOB, 0.3f32).unwrap();
+
+        assert_eq!(decoded, sample());
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        let mut blob = V1_BLOB;
+        blob[2] = 3;
+
+        assert_eq!(Series::<10, u32, f32>::decode(&blob, 0.3f32), Err(DecodeError::UnsupportedVersion(3)));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(Series::<10, u32, f32>::decode(b"XS\x01\x00\x00", 0.3f32), Err(DecodeError::BadMagic));
+        assert_eq!(Series::<10, u32, f32>::decode(&V1_BLOB[..20], 0.3f32), Err(DecodeError::Truncated));
+        assert_eq!(Series::<1, u32, f32>::decode(&V1_BLOB, 0.3f32), Err(DecodeError::Capacity));
+    }
+
+    #[test]
+    fn encode_needs_room() {
+        let mut buf = [0u8; 10];
+
+        assert_eq!(sample().encode(&mut buf), Err(EncodeError::BufferTooSmall));
+    }
+
+    fn ramp() -> Series<40, u32, i32> {
+        let mut timeseries: Series<40, u32, i32> = Series::new(0);
+
+        for step in 0..20_u32 {
+            assert!(timeseries.append_monotonic(100_000 + step * 10, 500 + step as i32));
+            assert!(timeseries.append_monotonic(100_000 + step * 10 + 5, 500 + step as i32));
+        }
+
+        timeseries
+    }
+
+    #[test]
+    fn delta_round_trip() {
+        let mut buf = [0u8; 512];
+        let written = ramp().encode_delta(&mut buf).unwrap();
+
+        assert_eq!(buf[2], DELTA_VERSION);
+
+        let decoded: Series<40, u32, i32> = Series::decode_delta(&buf[..written], 0).unwrap();
+
+        assert_eq!(decoded, ramp());
+    }
+
+    #[test]
+    fn delta_is_smaller_than_fixed_width() {
+        let mut fixed = [0u8; 512];
+        let mut delta = [0u8; 512];
+
+        let fixed = ramp().encode(&mut fixed).unwrap();
+        let delta = ramp().encode_delta(&mut delta).unwrap();
+
+        assert_eq!(fixed, 5 + 20 * 13);
+        assert_eq!(delta, 5 + 10 + 19 * 4);
+    }
+
+    #[test]
+    fn delta_round_trip_floats_and_decreases() {
+        let mut timeseries: Series<10, i16, f64> = Series::new(0.3f64);
+
+        assert!(timeseries.append_monotonic( -300_i16, 32.6f64));
+        assert!(timeseries.append_monotonic( -2_i16, 32.5f64));
+        assert!(timeseries.append_monotonic( 4_i16, -12.3f64));
+        assert!(timeseries.append_monotonic( i16::MAX, -120.0f64));
+
+        let mut buf = [0u8; 128];
+        let written = timeseries.encode_delta(&mut buf).unwrap();
+        let decoded: Series<10, i16, f64> = Series::decode_delta(&buf[..written], 0.3f64).unwrap();
+
+        assert_eq!(decoded, timeseries);
+        assert_eq!(timeseries.encode_delta(&mut buf[..written - 1]), Err(EncodeError::BufferTooSmall));
+    }
+
+    /// A value with no [`Delta`], which only the fixed-width layout can store.
+    #[derive(Debug, Clone, PartialEq)]
+    struct Level(u8);
+
+    impl Deviate for Level {
+        fn deviate(&self, other: &Self, _max_deviation: &Self) -> bool {
+            self != other
+        }
+    }
+
+    impl Encode for Level {
+        const SIZE: usize = 1;
+
+        fn write(&self, buf: &mut [u8]) {
+            buf[0] = self.0;
+        }
+
+        fn read(buf: &[u8]) -> Self {
+            Level(buf[0])
+        }
+    }
+
+    #[test]
+    fn decode_without_delta() {
+        let mut timeseries: Series<10, u32, Level> = Series::new(Level(0));
+
+        assert!(timeseries.append_monotonic( 1_u32, Level(3)));
+        assert!(timeseries.append_monotonic( 4_u32, Level(3)));
+        assert!(timeseries.append_monotonic( 6_u32, Level(1)));
+
+        let mut buf = [0u8; 64];
+        let written = timeseries.encode(&mut buf).unwrap();
+        let decoded: Series<10, u32, Level> = Series::decode(&buf[..written], Level(0)).unwrap();
+
+        assert_eq!(decoded, timeseries);
+    }
+
+    #[test]
+    fn decode_rejects_delta_layout() {
+        let mut buf = [0u8; 512];
+        let written = ramp().encode_delta(&mut buf).unwrap();
+
+        assert_eq!(Series::<40, u32, i32>::decode(&buf[..written], 0), Err(DecodeError::UnsupportedVersion(DELTA_VERSION)));
+        assert_eq!(Series::<10, u32, f32>::decode_delta(&V1_BLOB, 0.3f32), Ok(sample()));
+    }
+
+    #[test]
+    fn encode_rejects_too_many_buckets() {
+        let mut timeseries: Series<{ u16::MAX as usize + 1 }, u8, u8> = Series::new(0);
+
+        while !timeseries.buckets.is_full() {
+            timeseries.buckets.push(SerieEntry { range: Range { start: 0, end: None }, value: 0 }).unwrap();
+        }
+
+        let mut buf = [0u8; 8];
+
+        assert_eq!(timeseries.encode(&mut buf), Err(EncodeError::TooManyBuckets));
+        assert_eq!(timeseries.encode_delta(&mut buf), Err(EncodeError::TooManyBuckets));
+    }
+}
@@ -0,0 +1,53 @@
+use super::Deviate;
+
+macro_rules! deviate_unsigned {
+    ($($t:ty),*) => {$(
+        /// Symmetric: readings deviate when they differ by more than `max_deviation` in either
+        /// direction.
+        impl Deviate for $t {
+            fn deviate(&self, other: &Self, max_deviation: &Self) -> bool {
+                self.abs_diff(*other) > *max_deviation
+            }
+
+            fn equal_may_deviate(_max_deviation: &Self) -> bool {
+                false
+            }
+
+            /// Saturates at the bounds of the type instead of overflowing.
+            fn clamp_within(&self, reference: &Self, max_deviation: &Self) -> Self {
+                (*self).clamp(reference.saturating_sub(*max_deviation), reference.saturating_add(*max_deviation))
+            }
+        }
+    )*}
+}
+
+macro_rules! deviate_signed {
+    ($($t:ty),*) => {$(
+        /// Symmetric: readings deviate when they differ by more than `max_deviation` in either
+        /// direction. The difference is computed without overflowing.
+        impl Deviate for $t {
+            fn deviate(&self, other: &Self, max_deviation: &Self) -> bool {
+                self.abs_diff(*other) > max_deviation.unsigned_abs()
+            }
+
+            fn equal_may_deviate(_max_deviation: &Self) -> bool {
+                false
+            }
+
+            /// Saturates at `MAX` when the difference does not fit, as for `MIN` and `MAX`.
+            fn distance(&self, other: &Self) -> Self {
+                <$t>::try_from(self.abs_diff(*other)).unwrap_or(<$t>::MAX)
+            }
+
+            /// Saturates at the bounds of the type instead of overflowing.
+            fn clamp_within(&self, reference: &Self, max_deviation: &Self) -> Self {
+                let max_deviation = max_deviation.unsigned_abs();
+
+                (*self).clamp(reference.saturating_sub_unsigned(max_deviation), reference.saturating_add_unsigned(max_deviation))
+            }
+        }
+    )*}
+}
+
+deviate_unsigned!(u8, u16, u32, u64, u128);
+deviate_signed!(i8, i16, i32, i64, i128);
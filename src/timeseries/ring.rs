@@ -0,0 +1,79 @@
+use super::{Config, Deviate, SerieEntry, Series};
+
+/// A sliding window over the newest `N` buckets. Appending never fails for lack of space:
+/// when a new bucket is needed and the window is full, the oldest bucket is dropped.
+#[derive(Debug, Eq, PartialEq)]
+pub struct RingSeries<const N : usize, I, T> {
+    series: Series<N, I, T>
+}
+
+impl <const N : usize, I : Ord, T : Deviate> RingSeries<N, I, T> {
+    pub fn new(max_deviation: T) -> RingSeries<N, I, T> {
+        RingSeries { series: Series::configured(max_deviation, Config { evicting: true, ..Config::default() }) }
+    }
+
+    /// Appends like [`Series::append_monotonic`]; `false` is only returned for a reading that is
+    /// not later than the newest bucket.
+    pub fn append_monotonic(&mut self, at: I, value: T) -> bool where T: PartialEq {
+        self.series.append_monotonic(at, value)
+    }
+
+    pub fn oldest(&self) -> Option<&SerieEntry<I, T>> {
+        self.series.buckets.first()
+    }
+
+    pub fn newest(&self) -> Option<&SerieEntry<I, T>> {
+        self.series.buckets.last()
+    }
+
+    pub fn series(&self) -> &Series<N, I, T> {
+        &self.series
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use heapless::Vec;
+
+    use super::*;
+    use crate::timeseries::Range;
+
+    #[test]
+    fn wraps_past_capacity() {
+        let mut timeseries: RingSeries<3, u8, f32> = RingSeries::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 1_u8, 32.6f32));
+        assert!(timeseries.append_monotonic( 2_u8, 22.6f32));
+        assert!(timeseries.append_monotonic( 3_u8, 12.6f32));
+        assert!(timeseries.append_monotonic( 4_u8, 2.6f32));
+        assert!(timeseries.append_monotonic( 5_u8, 2.5f32));
+        assert!(timeseries.append_monotonic( 6_u8, -7.4f32));
+
+        let mut expected: Vec<SerieEntry<u8, f32>, 3> = Vec::new();
+        expected.push(SerieEntry { range: Range { start: 3, end: None }, value: 12.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 4, end: Some(5) }, value: 2.6 }).unwrap();
+        expected.push(SerieEntry { range: Range { start: 6, end: None }, value: -7.4 }).unwrap();
+
+        assert_eq!(timeseries.series().buckets, expected);
+        assert_eq!(timeseries.oldest().map(|b| *b.start()), Some(3));
+        assert_eq!(timeseries.newest().map(|b| *b.start()), Some(6));
+    }
+
+    #[test]
+    fn rejects_non_monotonic() {
+        let mut timeseries: RingSeries<1, u8, f32> = RingSeries::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 5_u8, 32.6f32));
+        assert!(!timeseries.append_monotonic( 5_u8, 12.6f32));
+        assert!(!timeseries.append_monotonic( 2_u8, 12.6f32));
+        assert_eq!(timeseries.oldest(), timeseries.newest());
+    }
+
+    #[test]
+    fn empty_has_no_oldest_or_newest() {
+        let timeseries: RingSeries<3, u8, f32> = RingSeries::new(0.3f32);
+
+        assert_eq!(timeseries.oldest(), None);
+        assert_eq!(timeseries.newest(), None);
+    }
+}
@@ -0,0 +1,66 @@
+use super::{Deviate, Range, SerieEntry};
+
+/// Compresses readings like [`super::Series`] but without bounded storage: only the open
+/// bucket is kept, and every bucket is handed back as soon as a reading closes it.
+#[derive(Debug, Eq, PartialEq)]
+pub struct StreamingCompressor<I, T> {
+    pub max_deviation: T,
+    current: Option<SerieEntry<I, T>>
+}
+
+impl <I : Ord, T : Deviate> StreamingCompressor<I, T> {
+    pub fn new(max_deviation: T) -> StreamingCompressor<I, T> {
+        StreamingCompressor { max_deviation, current: None }
+    }
+
+    /// Feeds a reading into the open bucket. When the reading deviates, the open bucket is
+    /// finalized and returned and the reading opens the next one. Readings that are not
+    /// strictly after the open bucket are ignored.
+    pub fn push(&mut self, at: I, value: T) -> Option<SerieEntry<I, T>> {
+        match &mut self.current {
+            Some(current) if at <= *current.range.latest() => None,
+            Some(current) if !current.value.deviate(&value, &self.max_deviation) => {
+                let _ = current.range.try_extend(at);
+                None
+            },
+            _ => self.current.replace(SerieEntry { range: Range::new(at), value })
+        }
+    }
+
+    /// Returns the bucket that is still open, if any.
+    pub fn finish(self) -> Option<SerieEntry<I, T>> {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_emits_on_transition() {
+        let mut compressor: StreamingCompressor<u8, f32> = StreamingCompressor::new(0.3f32);
+
+        assert_eq!(compressor.push( 1_u8, 32.6f32), None);
+        assert_eq!(compressor.push( 2_u8, 32.7f32), None);
+        assert_eq!(compressor.push( 3_u8, 12.3f32), Some(SerieEntry { range: Range { start: 1, end: Some(2) }, value: 32.6 }));
+        assert_eq!(compressor.push( 4_u8, 2.3f32), Some(SerieEntry { range: Range { start: 3, end: None }, value: 12.3 }));
+        assert_eq!(compressor.finish(), Some(SerieEntry { range: Range { start: 4, end: None }, value: 2.3 }));
+    }
+
+    #[test]
+    fn push_ignores_non_monotonic() {
+        let mut compressor: StreamingCompressor<u8, f32> = StreamingCompressor::new(0.3f32);
+
+        assert_eq!(compressor.push( 5_u8, 32.6f32), None);
+        assert_eq!(compressor.push( 5_u8, 12.3f32), None);
+        assert_eq!(compressor.push( 1_u8, 2.3f32), None);
+        assert_eq!(compressor.finish(), Some(SerieEntry { range: Range { start: 5, end: None }, value: 32.6 }));
+    }
+
+    #[test]
+    fn finish_empty() {
+        let compressor: StreamingCompressor<u8, f32> = StreamingCompressor::new(0.3f32);
+        assert_eq!(compressor.finish(), None);
+    }
+}
@@ -0,0 +1,127 @@
+use core::cmp::Ordering;
+
+use super::{Deviate, SerieEntry, Series};
+
+/// A borrowed, capacity-agnostic view of a [`Series`], so read-only code can be written once
+/// for every `N` instead of being monomorphized per capacity. Timestamps are ordered like in
+/// the viewed series, including the comparator of [`Series::new_with_cmp`].
+#[derive(Debug)]
+pub struct SeriesView<'a, I, T> {
+    pub max_deviation: &'a T,
+    pub buckets: &'a [SerieEntry<I, T>],
+    order: fn(&I, &I) -> Ordering
+}
+
+impl <'a, I, T> Clone for SeriesView<'a, I, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl <'a, I, T> Copy for SeriesView<'a, I, T> {}
+
+impl <const N : usize, I : Ord, T : Deviate> Series<N, I, T> {
+    pub fn view(&self) -> SeriesView<'_, I, T> {
+        SeriesView { max_deviation: &self.max_deviation, buckets: &self.buckets, order: self.order() }
+    }
+}
+
+impl <'a, I : Ord, T> SeriesView<'a, I, T> {
+    pub fn starts_at(&self) -> Option<&'a I> {
+        self.buckets.first().map(|x| &x.range.start)
+    }
+
+    /// The latest timestamp in the series. Buckets are monotonic, so this is always the
+    /// latest timestamp of the last bucket; debug builds assert that no earlier bucket reaches
+    /// past it.
+    pub fn ends_at(&self) -> Option<&'a I> {
+        let latest = self.buckets.last().map(|b| b.range.latest());
+
+        debug_assert!(
+            latest.map(|latest| self.buckets.iter().all(|b| (self.order)(b.range.latest(), latest).is_le())).unwrap_or(true),
+            "an earlier bucket ends after the last bucket"
+        );
+
+        latest
+    }
+
+    /// Returns the value of the bucket covering `at`, or `None` when `at` falls in a gap or
+    /// outside the series.
+    pub fn value_at(&self, at: &I) -> Option<&'a T> {
+        let index = self.buckets.partition_point(|b| (self.order)(&b.range.start, at).is_le()).checked_sub(1)?;
+        let b = &self.buckets[index];
+
+        if (self.order)(at, b.range.latest()).is_le() { Some(&b.value) } else { None }
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timeseries::{Range, SerieEntry};
+
+    fn describe(view: SeriesView<'_, u32, f32>) -> (usize, Option<&u32>, Option<&u32>, Option<&f32>) {
+        (view.len(), view.starts_at(), view.ends_at(), view.value_at(&2))
+    }
+
+    #[test]
+    fn view_works_for_any_capacity() {
+        let mut small: Series<2, u32, f32> = Series::new(0.3f32);
+        let mut large: Series<10, u32, f32> = Series::new(0.3f32);
+
+        assert!(small.append_monotonic( 1_u32, 32.6f32));
+        assert!(small.append_monotonic( 3_u32, 32.7f32));
+        assert!(large.append_monotonic( 2_u32, 12.3f32));
+        assert!(large.append_monotonic( 4_u32, 2.3f32));
+
+        assert_eq!(describe(small.view()), (1, Some(&1), Some(&3), Some(&32.6)));
+        assert_eq!(describe(large.view()), (2, Some(&2), Some(&4), Some(&12.3)));
+    }
+
+    #[test]
+    fn value_at_gap_and_outside() {
+        let mut timeseries: Series<10, u32, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.append_monotonic( 2_u32, 32.6f32));
+        assert!(timeseries.append_monotonic( 4_u32, 32.7f32));
+        assert!(timeseries.append_monotonic( 8_u32, 12.3f32));
+
+        assert_eq!(timeseries.value_at(&1), None);
+        assert_eq!(timeseries.value_at(&4), Some(&32.6));
+        assert_eq!(timeseries.value_at(&6), None);
+        assert_eq!(timeseries.value_at(&8), Some(&12.3));
+        assert_eq!(timeseries.value_at(&9), None);
+    }
+
+    #[test]
+    fn len_and_is_empty() {
+        let mut timeseries: Series<10, u32, f32> = Series::new(0.3f32);
+
+        assert!(timeseries.is_empty());
+
+        assert!(timeseries.append_monotonic( 2_u32, 32.6f32));
+
+        assert!(!timeseries.is_empty());
+        assert_eq!(timeseries.len(), 1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "an earlier bucket ends after the last bucket")]
+    fn ends_at_detects_corrupted_order() {
+        let mut timeseries: Series<10, u32, f32> = Series::new(0.3f32);
+
+        timeseries.buckets.push(SerieEntry { range: Range { start: 1, end: Some(9) }, value: 32.6 }).unwrap();
+        timeseries.buckets.push(SerieEntry { range: Range { start: 4, end: None }, value: 12.3 }).unwrap();
+
+        timeseries.view().ends_at();
+    }
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "derive")]
+
+use timeseries::timeseries::{Deviate, Series};
+
+#[derive(Debug, PartialEq, Deviate)]
+struct Reading {
+    temp: f32,
+    pub pressure: f32
+}
+
+#[derive(Debug, PartialEq, Deviate)]
+struct Pair(f32, Option<f64>);
+
+#[test]
+fn any_field_deviates() {
+    let max = Reading { temp: 0.5, pressure: 2.0 };
+
+    assert!(!Reading { temp: 20.0, pressure: 1000.0 }.deviate(&Reading { temp: 19.8, pressure: 999.0 }, &max));
+    assert!(Reading { temp: 20.0, pressure: 1000.0 }.deviate(&Reading { temp: 19.0, pressure: 1000.0 }, &max));
+    assert!(Reading { temp: 20.0, pressure: 1000.0 }.deviate(&Reading { temp: 20.0, pressure: 990.0 }, &max));
+}
+
+#[test]
+fn tuple_struct_fields() {
+    let max = Pair(0.5, Some(0.5));
+
+    assert!(!Pair(1.0, Some(1.0)).deviate(&Pair(0.9, Some(0.9)), &max));
+    assert!(Pair(1.0, Some(1.0)).deviate(&Pair(1.0, Some(0.0)), &max));
+}
+
+#[test]
+fn derived_struct_in_series() {
+    let mut timeseries: Series<10, u8, Reading> = Series::new(Reading { temp: 0.5, pressure: 2.0 });
+
+    assert!(timeseries.append_monotonic( 1_u8, Reading { temp: 20.0, pressure: 1000.0 }));
+    assert!(timeseries.append_monotonic( 2_u8, Reading { temp: 19.8, pressure: 999.5 }));
+    assert!(timeseries.append_monotonic( 3_u8, Reading { temp: 20.0, pressure: 990.0 }));
+
+    assert_eq!(timeseries.len(), 2);
+    assert_eq!(timeseries.buckets[0].end(), Some(&2));
+}
@@ -0,0 +1,29 @@
+use heapless::Vec;
+use timeseries::timeseries::{InvariantError, Range, SerieEntry, Series};
+
+#[test]
+fn rebuilds_series_from_buckets() {
+    let mut buckets: Vec<SerieEntry<u32, f32>, 10> = Vec::new();
+    buckets.push(SerieEntry::new(Range::ranged(1, 4), 32.6)).unwrap();
+    buckets.push(SerieEntry::new(Range::new(6), 12.3)).unwrap();
+
+    let mut timeseries = Series::from_buckets(0.3f32, buckets).unwrap();
+
+    assert_eq!(timeseries.value_at(&3), Some(&32.6));
+    assert_eq!(timeseries.ends_at(), Some(&6));
+    assert!(timeseries.append_monotonic(7, 12.2));
+    assert_eq!(timeseries.len(), 2);
+}
+
+#[test]
+fn rejects_invalid_buckets() {
+    let mut backwards: Vec<SerieEntry<u32, f32>, 10> = Vec::new();
+    backwards.push(SerieEntry::new(Range::ranged(4, 1), 32.6)).unwrap();
+
+    let mut unordered: Vec<SerieEntry<u32, f32>, 10> = Vec::new();
+    unordered.push(SerieEntry::new(Range::ranged(1, 4), 32.6)).unwrap();
+    unordered.push(SerieEntry::new(Range::new(3), 12.3)).unwrap();
+
+    assert_eq!(Series::from_buckets(0.3f32, backwards), Err(InvariantError::BackwardsRange { index: 0 }));
+    assert_eq!(Series::from_buckets(0.3f32, unordered), Err(InvariantError::NotMonotonic { index: 1 }));
+}
@@ -0,0 +1,101 @@
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
+/// Derives `Deviate` for a struct whose fields all implement it. The threshold is a value of
+/// the same struct, and a reading deviates when any field deviates against the corresponding
+/// field of the threshold.
+#[proc_macro_derive(Deviate)]
+pub fn derive_deviate(input: TokenStream) -> TokenStream {
+    match parse_struct(input) {
+        Ok((name, fields)) => expand(&name, &fields),
+        Err(message) => format!("compile_error!({:?});", message).parse().unwrap()
+    }
+}
+
+fn expand(name: &str, fields: &[String]) -> TokenStream {
+    let body = fields.iter()
+        .map(|field| format!("::timeseries::timeseries::Deviate::deviate(&self.{0}, &other.{0}, &max_deviation.{0})", field))
+        .collect::<Vec<_>>();
+    let body = if body.is_empty() { String::from("false") } else { body.join(" || ") };
+
+    format!(
+        "impl ::timeseries::timeseries::Deviate for {} {{
+            fn deviate(&self, other: &Self, max_deviation: &Self) -> bool {{
+                {}
+            }}
+        }}",
+        name, body
+    ).parse().unwrap()
+}
+
+/// Extracts the struct name and the accessors of its fields: the field names of a struct with
+/// named fields, or the indices of a tuple struct.
+fn parse_struct(input: TokenStream) -> Result<(String, Vec<String>), &'static str> {
+    let mut tokens = input.into_iter();
+
+    for token in tokens.by_ref() {
+        match token {
+            TokenTree::Ident(ident) if ident.to_string() == "struct" => break,
+            TokenTree::Ident(ident) if ident.to_string() == "enum" || ident.to_string() == "union" => {
+                return Err("Deviate can only be derived for structs")
+            },
+            _ => {}
+        }
+    }
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return Err("Deviate can only be derived for structs")
+    };
+
+    match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            let fields = split_fields(group.stream()).iter().map(|field| field_name(field)).collect::<Option<_>>();
+            fields.map(|fields| (name, fields)).ok_or("unsupported field declaration")
+        },
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            let fields = (0..split_fields(group.stream()).len()).map(|index| index.to_string()).collect();
+            Ok((name, fields))
+        },
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => Err("Deviate cannot be derived for generic structs"),
+        _ => Ok((name, Vec::new()))
+    }
+}
+
+/// Splits the body of a struct into one token list per field. Commas inside generic
+/// arguments such as `Foo<A, B>` are not separators, so angle brackets are tracked.
+fn split_fields(body: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut fields = Vec::new();
+    let mut field = Vec::new();
+    let mut depth = 0usize;
+
+    for token in body {
+        if let TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    fields.push(core::mem::take(&mut field));
+                    continue;
+                },
+                _ => {}
+            }
+        }
+
+        field.push(token);
+    }
+
+    if !field.is_empty() {
+        fields.push(field);
+    }
+
+    fields
+}
+
+/// The name of a named field: the identifier right before the first `:`, after any
+/// attributes and visibility.
+fn field_name(field: &[TokenTree]) -> Option<String> {
+    field.windows(2).find_map(|pair| match pair {
+        [TokenTree::Ident(ident), TokenTree::Punct(punct)] if punct.as_char() == ':' => Some(ident.to_string()),
+        _ => None
+    })
+}